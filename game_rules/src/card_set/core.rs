@@ -22,3 +22,26 @@ lazy_static! {
         }
     };
 }
+
+lazy_static! {
+    // Replacements of the game- and player card are not part of the set, because
+    // their identifiers are already taken by the default cards.
+    static ref ALL_CARDS: [&'static Card; 0] = {
+        [
+            //
+        ]
+    };
+}
+
+/// Returns all cards of the core set.
+pub fn cards() -> Vec<Card> {
+    ALL_CARDS.iter().map(|&c| c.clone()).collect()
+}
+
+/// Returns the player card for games in hardcore mode.
+///
+/// This card replaces the default player card when it's provided as
+/// [`SetupConfig::player_card`].
+pub fn hardcore_player_card() -> Card {
+    HARDCORE_PLAYER_CARD.clone()
+}
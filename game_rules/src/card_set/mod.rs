@@ -1,9 +1,14 @@
 //! Contains all game card definitions.
 
+use game_system::prelude::*;
+
 pub mod core;
 pub mod test;
 
 /// Enumeration of all known sets of cards.
+///
+/// Install a set into [`CardService`] through [`CardService::install_set`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CardSet {
     /// Core card set, see module [`core`].
     Core = 0,
@@ -17,3 +22,32 @@ impl From<CardSet> for u32 {
         x as u32
     }
 }
+
+impl CardSetTrait for CardSet {
+    type Card = Card;
+
+    fn set_id(&self) -> u32 {
+        (*self).into()
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            CardSet::Core => "Core",
+            CardSet::Test => "Test",
+        }
+    }
+
+    fn version(&self) -> u32 {
+        match self {
+            CardSet::Core => 1,
+            CardSet::Test => 1,
+        }
+    }
+
+    fn cards(&self) -> Vec<Card> {
+        match self {
+            CardSet::Core => core::cards(),
+            CardSet::Test => test::cards(),
+        }
+    }
+}
@@ -75,3 +75,8 @@ lazy_static! {
         ]
     };
 }
+
+/// Returns all cards of the test set.
+pub fn cards() -> Vec<Card> {
+    ALL_CARDS.iter().map(|&c| c.clone()).collect()
+}
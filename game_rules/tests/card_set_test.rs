@@ -0,0 +1,65 @@
+extern crate game_rules;
+
+use game_rules::card_set::{self, CardSet};
+use game_rules::game_system::card::PLAYER_CARD_ID;
+use game_rules::action::start_game;
+use game_rules::game_system::prelude::*;
+use game_rules::game_system::setup::error::SetupError;
+use game_rules::trigger::start_game_trigger;
+
+#[test]
+/// All card sets can be installed into one game.
+fn install_card_sets() {
+    let mut game = Machine::new(&Default::default()).expect("Error creating new game!");
+    game.cards.install_set(&CardSet::Core).unwrap();
+    game.cards.install_set(&CardSet::Test).unwrap();
+
+    let names: Vec<&str> = game.cards.sets().map(|s| s.name).collect();
+    assert_eq!(names, vec!["Core", "Test"]);
+    assert_eq!(game.cards.cards_in_set(CardSet::Test.set_id()).count(), 2);
+    assert_eq!(game.cards.cards_in_set(CardSet::Core.set_id()).count(), 0);
+}
+
+#[test]
+/// The hardcore player card replaces the default player card.
+fn hardcore_player_card() {
+    let hardcore = card_set::core::hardcore_player_card();
+    assert_eq!(hardcore.id(), PLAYER_CARD_ID);
+    assert_eq!(hardcore.name, "Player card");
+    assert_eq!(hardcore.get_value_default(&EntityTags::Health), 15);
+}
+
+#[test]
+/// Games in hardcore mode start with players built from the hardcore player card.
+fn hardcore_game_start() {
+    let config = SetupConfig {
+        player_card: Some(card_set::core::hardcore_player_card()),
+        ..Default::default()
+    };
+    let mut game = Machine::new(&config).expect("Error creating new game!");
+    game.triggers.add_trigger(start_game_trigger::<AnyStack>);
+    let game = start_game(game).expect("Game unexpectedly finished");
+
+    let players: Vec<_> = game
+        .entities
+        .with_proto::<prototype::Player>()
+        .collect();
+    assert_eq!(players.len(), 2);
+    for player in players {
+        assert_eq!(player.get_value_default(&EntityTags::Health), 15);
+        assert_eq!(player.get_value_default(&EntityTags::StartHandSize), 5);
+    }
+}
+
+#[test]
+/// Player cards which don't replace the default player card are refused.
+fn invalid_player_card() {
+    let config = SetupConfig {
+        player_card: Some(Card::new_with_id(CardId::new(9, 1))),
+        ..Default::default()
+    };
+    match Machine::new(&config) {
+        Err(SetupError::InvalidPlayerCard(id)) => assert_eq!(id, CardId::new(9, 1)),
+        x => panic!("Unexpected result {:?}", x),
+    }
+}
@@ -1,6 +1,7 @@
 //! Specialized implementation of the [`Card`] game-object.
 
 use medici_core::prefab::card::CardStruct;
+use medici_core::service::CardService;
 
 use state_machine::state::prelude::{TimingItem, TriggerItem};
 use tag::EntityTags;
@@ -13,13 +14,31 @@ pub use medici_core::prefab::card::PLAYER_CARD_ID;
 /// The specialized [`Card`] structure.
 pub type Card = CardStruct<EntityTags, TimingItem, TriggerItem>;
 
+/// Builds a card service holding the default game- and player card.
+///
+/// These cards are necessary to setup a new game.
+pub fn default_cards() -> CardService<Card> {
+    let mut cards = CardService::new();
+    cards
+        .register_card(GAME_CARD.clone())
+        .expect("The game card identifier is reserved");
+    cards
+        .register_card(PLAYER_CARD.clone())
+        .expect("The player card identifier is reserved");
+    cards
+}
+
 #[macro_export]
 /// Use this macro to build implementations of new cards.
+///
+/// Each trigger accepts an optional `CONDITION = ..;` clause after it's `TRIGGER`.
+/// The trigger is only executed when the condition holds for the machine.
 macro_rules! card_impl {
     (
     	ID = $id:expr ; NAME = $name:expr ;
     	properties { $($prop_name:path = $prop_value:expr;)* }
-    	triggers { $( $method:ident [ TIMING = $timing:path ; TRIGGER = $trigger:path ; ] =>
+    	triggers { $( $method:ident [ TIMING = $timing:path ; TRIGGER = $trigger:path ;
+    		$( $condition:tt )* ] =>
     		| $machine_name:ident | $method_impl:block )* }
 	) => {{
 		#[allow(unused_imports)]
@@ -29,7 +48,7 @@ macro_rules! card_impl {
     	use $crate::prelude::CardBuilder;
 		//
 		let mut c = $crate::prelude::Card::new_with_id($id);
-		c.name = $name;
+		c.name = $name.into();
 		//
 		$( c.set_value($prop_name, $prop_value); )*
 		// A block argument already has opening and closing braces!
@@ -42,12 +61,31 @@ macro_rules! card_impl {
 				$method_impl
 			}
 
-			c.triggers.add_trigger($method::<AnyStack>);
+			let options = $crate::re_export::service::TriggerOptions {
+				name: Some(stringify!($method).into()),
+				..Default::default()
+			};
+			card_impl!(@register c, $method, options, $timing, $trigger; $( $condition )*);
 		)*
 		//
 		c
     }};
 
+    /* Registration of a trigger without condition */
+    (@register $c:ident, $method:ident, $options:ident, $timing:path, $trigger:path;) => {
+		$c.triggers.add_trigger_with($method::<$crate::prelude::AnyStack>, $options);
+    };
+
+    /* Registration of a trigger with it's (single) condition */
+    (@register $c:ident, $method:ident, $options:ident, $timing:path, $trigger:path;
+    	CONDITION = $condition:expr ;
+	) => {
+		let condition: fn(
+			&$crate::prelude::Machine<$crate::prelude::Trigger<$timing, $trigger>, $crate::prelude::AnyStack>
+		) -> bool = $condition;
+		$c.triggers.add_conditional_trigger($method::<$crate::prelude::AnyStack>, condition, $options);
+    };
+
     (
     	ID = $id:expr ; NAME = $name:expr ;
     	properties { $($prop_name:path = $prop_value:expr;)* }
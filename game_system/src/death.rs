@@ -0,0 +1,95 @@
+//! Module containing the death phase of the state machine.
+//!
+//! Entities can die as consequence of any effect. Dead entities are processed after the
+//! effect chain has completed, by pushing down into the [`DeathEffect`] states once
+//! for each dead entity.
+
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+use medici_core::ct;
+use medici_core::ctstack::CTStack;
+use medici_core::error::custom_type::TransactionUnpackError;
+use medici_core::error::{ErrorKind, FrontendErrorExt, MachineError};
+use medici_core::function::State;
+use medici_core::marker;
+use medici_core::prefab::runtime::{exec_trigger_stepped, fetch_triggers};
+use medici_core::stm::checked::{PullupInto, PushdownInto, TransitionInto};
+
+use state_machine::machine::Machine;
+use state_machine::state::leaf::triggerable::Death;
+use state_machine::state::leaf::{Peri, Post, Pre};
+use state_machine::state::toplevel::{DeathEffect, Effect};
+use state_machine::transaction::{self, TransactionItem};
+
+type D1<A, CTS> = ct!(DeathEffect<Pre, Death> => CTS, from Effect<A>);
+
+/// Processes all dead entities of the provided machine.
+///
+/// This method is called after each effect chain by [`exec_triggers`].
+///
+/// The death triggers of each dead entity are executed before all of these entities
+/// are removed. Death triggers can cause other entities to die, so this process
+/// repeats until no entity died anymore.
+pub fn exec_death_phase<A, CTS>(
+    mut machine: Machine<Effect<A>, CTS>,
+) -> Result<Machine<Effect<A>, CTS>, MachineError>
+where
+    A: marker::Actionable + State + Debug + Send + 'static,
+    A::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    loop {
+        let dead = machine.dead_entities();
+        if dead.is_empty() {
+            return Ok(machine);
+        }
+
+        // Entities which died simultaneously all respond to their death before
+        // any of them is removed.
+        for &entity in &dead {
+            let pre: Machine<DeathEffect<Pre, Death>, D1<A, CTS>> =
+                machine.pushdown(transaction::Death { entity });
+            let post = exec_death(pre)?;
+            machine = post.pullup()?;
+        }
+
+        // Death triggers can heal or remove entities, only the entities which are
+        // still dead are removed.
+        let still_dead = machine.dead_entities();
+        for entity in dead.into_iter().filter(|e| still_dead.contains(e)) {
+            let removed = machine.remove_entity(entity);
+            if let Err(e) = removed {
+                return Err(e.infuse(ErrorKind::LogicError, || machine));
+            }
+        }
+    }
+}
+
+/// Executes the death triggers, of each timing, for the entity within the
+/// transaction of the provided machine.
+fn exec_death<CTS>(
+    pre: Machine<DeathEffect<Pre, Death>, CTS>,
+) -> Result<Machine<DeathEffect<Post, Death>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    let transaction = pre.transaction;
+
+    // Pre
+    let listeners = fetch_triggers(&pre);
+    // IMMUT REBIND
+    let pre = unsafe { exec_trigger_stepped(pre, listeners)? };
+
+    // Peri
+    let peri: Machine<DeathEffect<Peri, Death>, CTS> = pre.transition(transaction);
+    let listeners = fetch_triggers(&peri);
+    // IMMUT REBIND
+    let peri = unsafe { exec_trigger_stepped(peri, listeners)? };
+
+    // Post
+    let post: Machine<DeathEffect<Post, Death>, CTS> = peri.transition(transaction);
+    let listeners = fetch_triggers(&post);
+    unsafe { exec_trigger_stepped(post, listeners) }
+}
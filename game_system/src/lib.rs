@@ -31,18 +31,32 @@ extern crate value_from_type_macros;
 extern crate value_from_type_traits;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "serde-support")]
+extern crate serde;
+#[cfg(feature = "serde-support")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "card_loader")]
+extern crate toml;
 
 // Medici opinionated framework.
 extern crate medici_core;
+extern crate medici_derive;
 
 #[macro_use]
 pub mod card;
+pub mod death;
 pub mod entity;
+pub mod lifecycle;
+#[cfg(feature = "card_loader")]
+pub mod loader;
 pub mod prototype;
 pub mod runtime;
 pub mod setup;
+pub mod snapshot;
 pub mod state_machine;
 pub mod tag;
+pub mod zone;
 
 /// Exported types from [`medici_core`].
 ///
@@ -54,6 +68,8 @@ pub mod re_export {
     pub use medici_core::marker;
     pub use medici_core::service;
     pub use medici_core::stm::checked::{PullupFrom, PushdownFrom, TransitionFrom};
+    pub use medici_core::stm::graph::StateGraph;
+    pub use medici_core::stm::transitions::{TransitionEdge, TransitionKind};
     pub use medici_core::storage;
     // Macro re-exported
     pub use medici_core::ct;
@@ -74,8 +90,9 @@ pub mod prelude {
     pub use medici_core::ctstack::*;
     pub use medici_core::error::{self, ErrorKind, FrontendErrorExt, HydratedErrorExt, MachineError};
     pub use medici_core::function::{self, ArrayStorageCompliance, Card as CardTrait, CardBuilder,
-                                    CardId, Entity as EntityTrait, EntityBuilder, EntityId,
-                                    Identifiable, IndexedStorageCompliance, ServiceCompliance,
+                                    CardId, CardSet as CardSetTrait, Entity as EntityTrait,
+                                    EntityBuilder, EntityId, Identifiable,
+                                    IndexedStorageCompliance, ServiceCompliance,
                                     StackStorageCompliance};
     pub use medici_core::stm::checked::{PullupInto, PushdownInto, TransitionInto};
     pub use medici_core::transaction::{pack_transaction, unpack_transaction};
@@ -89,12 +106,13 @@ pub mod prelude {
 
     pub use card::Card;
     pub use entity::{Entity, GAME_E_ID};
-    pub use state_machine::config::SetupConfig;
+    pub use state_machine::config::{DeckList, DeckRules, SetupConfig};
     pub use state_machine::machine::Machine;
     pub use state_machine::state::leaf::triggerable::*;
     pub use state_machine::state::leaf::*;
     pub use state_machine::state::toplevel::*;
     pub use tag::EntityTags;
+    pub use zone::ZoneKind;
 
     // Transactions and Prototypes are NOT re-exported within the module
     // because their names could clash with States.
@@ -110,7 +128,6 @@ mod tests {
     use prelude::error::custom_type::MissingEntityError;
     use prelude::*;
     use re_export::*;
-    use std::marker::PhantomData;
 
     #[test]
     fn failure_derive() {
@@ -125,15 +142,9 @@ mod tests {
     fn checked_transitions() {
         // Build a new machine to reuse internal parts to build a custom one.
         let machine = Machine::new(&Default::default()).expect("Error building machine");
-        let machine: Machine<Action<Start>, EmptyStack> = Machine {
-            state: PhantomData,
-            history: PhantomData,
-            transaction: transaction::Epsilon,
-            //
-            transactions: machine.transactions,
-            entities: machine.entities,
-            triggers: machine.triggers,
-        };
+        let (_, parts) = machine.into_parts();
+        let machine: Machine<Action<Start>, EmptyStack> =
+            unsafe { Machine::from_parts(transaction::Epsilon, parts) };
 
         println!("START\n{:?}\n", machine);
         let push: Machine<Effect<Start>, _> =
@@ -148,16 +159,11 @@ mod tests {
     fn invalid_transition() {
         // Build a new machine to reuse internal parts to build a custom one.
         let machine = Machine::new(&Default::default()).expect("Error building machine");
-        let machine: Machine<Effect<Start>, EmptyStack> = Machine {
-            state: PhantomData,
-            history: PhantomData,
-            transaction: transaction::Epsilon,
-            //
-            transactions: machine.transactions,
-            entities: machine.entities,
-            triggers: machine.triggers,
-        };
-        // This is an invalid pullup because the transition history is empty.
+        let (_, parts) = machine.into_parts();
+        type History = ct!(Effect<Start> => EmptyStack, from Action<Start>);
+        let machine: Machine<Effect<Start>, History> =
+            unsafe { Machine::from_parts(transaction::Epsilon, parts) };
+        // This is an invalid pullup because no transaction was stored by a pushdown.
         let pull: Result<Machine<Action<Start>, _>, _> = PullupFrom::pullup_from(machine);
         assert!(pull.is_err());
     }
@@ -0,0 +1,152 @@
+//! Module containing the lifecycle of entities built from cards.
+//!
+//! The triggers defined on a [`Card`] are dormant, they only respond to the
+//! running game once they're activated for an entity which is in play.
+//! Activated triggers are owned by that entity and are deactivated again
+//! when the entity leaves play.
+//!
+//! Entities on the board die when their damage reaches their health, see [`exec_death_phase`]
+//! for the processing of dead entities.
+
+use medici_core::ctstack::CTStack;
+use medici_core::error::custom_type::{MissingEntityError, OverflowError};
+use medici_core::function::{CardId, EntityId, Identifiable, State};
+use medici_core::marker;
+use medici_core::service::EntityService;
+use medici_core::storage::TriggerHandle;
+
+use card::Card;
+use entity::Entity;
+use state_machine::machine::Machine;
+use tag::EntityTags;
+use zone::ZoneKind;
+
+pub mod error {
+    //! Definitions for all possible errors thrown when building entities
+    //! from cards.
+
+    use failure::Fail;
+    use medici_core::error::custom_type::{MissingCardError, OverflowError};
+    use medici_core::function::CardId;
+
+    #[derive(Debug, Fail)]
+    /// Enumeration of possible errors when spawning an entity from a card.
+    pub enum SpawnError {
+        #[fail(display = "The requested card is not registered on the machine")]
+        /// Thrown when the card is not known to the card service.
+        MissingCard(#[fail(cause)] MissingCardError<CardId>),
+
+        #[fail(display = "Maximum entity limit reached. Increase the allowed amount of entities!")]
+        /// Thrown when max entities is overflown.
+        EntityOverflow(#[fail(cause)] OverflowError),
+    }
+
+    impl From<MissingCardError<CardId>> for SpawnError {
+        fn from(x: MissingCardError<CardId>) -> Self {
+            SpawnError::MissingCard(x)
+        }
+    }
+
+    impl From<OverflowError> for SpawnError {
+        fn from(x: OverflowError) -> Self {
+            SpawnError::EntityOverflow(x)
+        }
+    }
+}
+use self::error::SpawnError;
+
+impl<X, CTS> Machine<X, CTS>
+where
+    X: marker::TopLevel + State + Send,
+    CTS: CTStack + Send,
+{
+    /// Creates a new entity from the card registered under the provided identifier.
+    ///
+    /// The properties of the card are copied into the new entity and the provided owner
+    /// is recorded as controller of the new entity.
+    /// The triggers of the card are NOT activated, this happens once the entity enters
+    /// play, see [`Machine::activate_entity`].
+    pub fn spawn_from_card(
+        &mut self,
+        card_id: CardId,
+        owner: EntityId,
+    ) -> Result<&mut Entity, SpawnError> {
+        let card = self.cards.get_card(card_id)?;
+        let entity = build_card_entity(&mut self.entities, card)?;
+        entity.owner = Some(owner);
+        Ok(entity)
+    }
+
+    /// Registers the triggers of the card the provided entity was built from, owned
+    /// by that entity.
+    ///
+    /// Nothing is activated when the entity was not built from a registered card.
+    /// This MUST be called whenever the entity enters play, which happens when it's moved
+    /// onto the board, see [`move_entity`].
+    pub fn activate_entity(&mut self, entity: EntityId) -> Vec<TriggerHandle> {
+        let card_id = match self.entities.get(entity).ok().and_then(|e| e.card_id) {
+            Some(card_id) => card_id,
+            None => return vec![],
+        };
+        match self.cards.get_card(card_id) {
+            Ok(card) => self.triggers.install_triggers(&card.triggers, entity),
+            Err(_) => vec![],
+        }
+    }
+
+    /// Unregisters all triggers owned by the provided entity.
+    ///
+    /// This MUST be called whenever the entity leaves play, so it's triggers
+    /// stop responding to the game.
+    /// The amount of deactivated triggers is returned.
+    pub fn deactivate_entity(&mut self, entity: EntityId) -> usize {
+        self.triggers.remove_triggers_owned_by(entity)
+    }
+
+    /// Removes the provided entity from play.
+    ///
+    /// The triggers owned by the entity are deactivated and the entity is taken out
+    /// of it's zone before the entity itself is removed.
+    pub fn remove_entity(
+        &mut self,
+        entity: EntityId,
+    ) -> Result<Entity, MissingEntityError<EntityId>> {
+        self.deactivate_entity(entity);
+        // Entities are not required to be within a zone.
+        let _ = self.zones.remove(entity);
+        self.entities.remove_entity(entity)
+    }
+
+    /// Returns all entities which died.
+    ///
+    /// An entity is dead when it's on the board, has health and the damage it has
+    /// taken is equal to or more than that health.
+    /// The game and player entities never die, neither do cards in a deck or hand.
+    pub fn dead_entities(&self) -> Vec<EntityId> {
+        self.entities
+            .iter()
+            .filter(|e| match self.zones.position_of(e.id()) {
+                Some(position) => position.zone == ZoneKind::Board,
+                None => false,
+            })
+            .filter(|e| match e.get_value(&EntityTags::Health) {
+                Ok(health) => e.get_value_default(&EntityTags::Damage) >= health,
+                Err(_) => false,
+            })
+            .map(|e| e.id())
+            .collect()
+    }
+}
+
+/// Creates a new entity holding the properties of the provided card.
+fn build_card_entity<'a>(
+    entities: &'a mut EntityService<Entity>,
+    card: &Card,
+) -> Result<&'a mut Entity, OverflowError> {
+    let entity = entities.new_entity()?;
+    entity
+        .state
+        .extend(card.state.iter().map(|(k, v)| (k.clone(), *v)));
+    entity.card_id = Some(card.id());
+    Ok(entity)
+}
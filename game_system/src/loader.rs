@@ -0,0 +1,236 @@
+//! Module containing a loader for card sets defined within TOML files.
+//!
+//! Enable the `card_loader` feature to use this module.
+//!
+//! Each card is an entry of the `card` array. Properties are referenced by the name
+//! of their [`EntityTags`] variant and triggers by the name of their callback within
+//! the provided [`TriggerRegistry`].
+//!
+//! ```toml
+//! [[card]]
+//! set = 1
+//! id = 1
+//! name = "Wizard"
+//! triggers = ["print_on_play"]
+//!
+//! [card.properties]
+//! Attack = 5
+//! Health = 3
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use toml;
+
+use medici_core::error::custom_type::IDCollisionError;
+use medici_core::function::{CardBuilder, CardId, Identifiable};
+use medici_core::service::trigger::TriggerOptions;
+use medici_core::service::CardService;
+
+use card::Card;
+use snapshot::TriggerRegistry;
+use tag::EntityTags;
+
+pub mod error {
+    //! Definitions for all possible errors thrown when loading cards.
+
+    use std::io;
+
+    use failure::Fail;
+    use medici_core::error::custom_type::{IDCollisionError, MissingTriggerError};
+    use medici_core::function::CardId;
+    use toml;
+
+    use tag::UnknownTagError;
+
+    #[derive(Debug, Fail)]
+    #[fail(display = "{:}:{:}: {:}", file, line, kind)]
+    /// Error thrown when a card file could not be loaded.
+    pub struct CardLoadError {
+        /// The file which was being loaded.
+        pub file: String,
+        /// Line within the file where the error was detected, starting at 1.
+        ///
+        /// The line is 0 when the error doesn't relate to the contents of the file.
+        /// Lines of invalid cards are best-effort, see [`load_cards_str`].
+        pub line: usize,
+        /// The reason why loading failed.
+        #[fail(cause)]
+        pub kind: CardLoadErrorKind,
+    }
+
+    #[derive(Debug, Fail)]
+    /// Enumeration of possible reasons why loading a card file failed.
+    pub enum CardLoadErrorKind {
+        #[fail(display = "The file could not be read")]
+        /// Thrown when the file could not be read.
+        Io(#[fail(cause)] io::Error),
+
+        #[fail(display = "The file is not a valid card file")]
+        /// Thrown when the contents of the file are not valid TOML, or not structured
+        /// like a card file.
+        Syntax(#[fail(cause)] toml::de::Error),
+
+        #[fail(display = "The card uses an unknown property")]
+        /// Thrown when a property is not a known entity tag.
+        UnknownTag(#[fail(cause)] UnknownTagError),
+
+        #[fail(display = "The card identifier is already used")]
+        /// Thrown when a card identifier is used twice, or is already registered.
+        DuplicateCard(#[fail(cause)] IDCollisionError<CardId>),
+
+        #[fail(display = "The card uses an unknown trigger")]
+        /// Thrown when a trigger is not known to the registry.
+        UnknownTrigger(#[fail(cause)] MissingTriggerError<String>),
+    }
+}
+use self::error::{CardLoadError, CardLoadErrorKind};
+
+/// Header of each card definition within a card file.
+const CARD_HEADER: &str = "[[card]]";
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CardFile {
+    #[serde(default)]
+    card: Vec<CardDefinition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CardDefinition {
+    set: u32,
+    id: u32,
+    name: String,
+    #[serde(default)]
+    properties: BTreeMap<String, u32>,
+    #[serde(default)]
+    triggers: Vec<String>,
+}
+
+/// Loads all cards defined within the provided file into the provided service.
+///
+/// The identifiers of the loaded cards are returned in order of definition.
+/// Nothing is registered when any card of the file is invalid.
+pub fn load_cards<P: AsRef<Path>>(
+    path: P,
+    registry: &TriggerRegistry,
+    cards: &mut CardService<Card>,
+) -> Result<Vec<CardId>, CardLoadError> {
+    let path = path.as_ref();
+    let file = path.display().to_string();
+    let source = fs::read_to_string(path).map_err(|e| CardLoadError {
+        file: file.clone(),
+        line: 0,
+        kind: CardLoadErrorKind::Io(e),
+    })?;
+    load_cards_str(&source, &file, registry, cards)
+}
+
+/// Loads all cards defined within the provided source into the provided service.
+///
+/// The provided file name is only used to report errors, see [`load_cards`].
+///
+/// Errors within a card definition are reported at the line assigning the invalid value.
+/// These lines are found by scanning the source, so values which are not assigned on
+/// their own line, eg within inline tables, are reported at the header of their card.
+pub fn load_cards_str(
+    source: &str,
+    file: &str,
+    registry: &TriggerRegistry,
+    cards: &mut CardService<Card>,
+) -> Result<Vec<CardId>, CardLoadError> {
+    let error = |line: usize, kind: CardLoadErrorKind| CardLoadError {
+        file: file.to_string(),
+        line,
+        kind,
+    };
+
+    let definitions: CardFile = toml::from_str(source).map_err(|e| {
+        let line = e.line_col().map(|(line, _)| line + 1).unwrap_or(0);
+        error(line, CardLoadErrorKind::Syntax(e))
+    })?;
+
+    let mut built: Vec<Card> = vec![];
+    for (idx, definition) in definitions.card.into_iter().enumerate() {
+        let card_id = CardId::new(definition.set, definition.id);
+        let collides = cards.get_card(card_id).is_ok() || built.iter().any(|c| c.id() == card_id);
+        if collides {
+            let line = definition_line(source, idx, |_| false);
+            let kind = CardLoadErrorKind::DuplicateCard(IDCollisionError(card_id));
+            return Err(error(line, kind));
+        }
+
+        let mut card = Card::new_with_id(card_id);
+        card.name = definition.name.into();
+        for (tag, value) in definition.properties {
+            match tag.parse::<EntityTags>() {
+                Ok(tag) => card.set_value(tag, value),
+                Err(e) => {
+                    let line = definition_line(source, idx, |l| assigns_key(l, &tag));
+                    return Err(error(line, CardLoadErrorKind::UnknownTag(e)));
+                }
+            };
+        }
+        for callback in definition.triggers {
+            let options = TriggerOptions {
+                name: Some(callback.clone().into()),
+                ..Default::default()
+            };
+            if let Err(e) = card
+                .triggers
+                .add_registered_trigger(registry, &callback, options)
+            {
+                let quoted = format!("\"{}\"", callback);
+                let line = definition_line(source, idx, |l| l.contains(&quoted));
+                return Err(error(line, CardLoadErrorKind::UnknownTrigger(e)));
+            }
+        }
+        built.push(card);
+    }
+
+    let ids = built.iter().map(|c| c.id()).collect();
+    for card in built {
+        // Collisions were verified before registering any card.
+        let _ = cards.register_card(card);
+    }
+    Ok(ids)
+}
+
+/// Returns the line, starting at 1, of the requested card definition.
+///
+/// The first line of the definition matching the provided predicate is returned, or
+/// the line of the definition header if no line matches.
+fn definition_line<F>(source: &str, card: usize, predicate: F) -> usize
+where
+    F: Fn(&str) -> bool,
+{
+    let mut lines = source.lines().enumerate();
+    let header = lines.by_ref().filter(|&(_, l)| is_card_header(l)).nth(card);
+    let header = match header {
+        Some((idx, _)) => idx,
+        None => return 0,
+    };
+    let found = lines
+        .take_while(|&(_, l)| !is_card_header(l))
+        .find(|&(_, l)| predicate(l))
+        .map(|(idx, _)| idx);
+    found.unwrap_or(header) + 1
+}
+
+/// Returns true if the provided line is the header of a card definition.
+///
+/// Whitespace and trailing comments are ignored, eg `[[ card ]] # Wizard`.
+fn is_card_header(line: &str) -> bool {
+    let content = line.split('#').next().unwrap_or("");
+    let header: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+    header == CARD_HEADER
+}
+
+/// Returns true if the provided line assigns a value to the provided key.
+fn assigns_key(line: &str, key: &str) -> bool {
+    let line = line.trim_start();
+    line.starts_with(key) && line[key.len()..].trim_start().starts_with('=')
+}
@@ -1,7 +1,5 @@
 //! Specialized prototypes for our state machine.
 
-use value_from_type_macros::value_from_type;
-
 mod definition;
 mod game;
 
@@ -1,7 +1,48 @@
 //! Module pending removal
 
-use medici_core::build_exec_triggers_checked;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+use medici_core::build_exec_recurse_checked;
+use medici_core::error::custom_type::TransactionUnpackError;
+use medici_core::function::State;
+use medici_core::marker;
+use medici_core::value_from_type_traits::IntoEnum;
 use prelude::*;
+use state_machine::transaction::TransactionItem;
+
+pub use death::exec_death_phase;
+
+mod checked {
+    use medici_core::build_exec_triggers_checked;
+    use prelude::*;
+
+    // Invoking this macro must be accompanied with importing all used types!
+    build_exec_triggers_checked!(Machine);
+}
+
+/// Takes the provided machine (in [`Effect`] state) and executes direct and indirect
+/// triggers.
+///
+/// Entities which died during the effect chain are processed afterwards,
+/// see [`exec_death_phase`].
+pub fn exec_triggers<A, CTS>(
+    machine: Machine<Effect<A>, CTS>,
+    transaction: A::Transaction,
+) -> Result<Machine<Effect<A>, CTS>, MachineError>
+where
+    A: marker::Actionable + State + IntoEnum<TriggerItem> + Debug + Clone + Send + Sync + 'static,
+    A::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+    Machine<Effect<A>, CTS>: TransitionInto<Machine<Trigger<Pre, A>, CTS>, CTS>,
+    Machine<Trigger<Pre, A>, CTS>: TransitionInto<Machine<Trigger<Peri, A>, CTS>, CTS>,
+    Machine<Trigger<Peri, A>, CTS>: TransitionInto<Machine<Trigger<Post, A>, CTS>, CTS>,
+    Machine<Trigger<Post, A>, CTS>: TransitionInto<Machine<Effect<A>, CTS>, CTS>,
+{
+    let machine = checked::exec_triggers(machine, transaction)?;
+    exec_death_phase(machine)
+}
 
 // Invoking this macro must be accompanied with importing all used types!
-build_exec_triggers_checked!(Machine);
+build_exec_recurse_checked!(Machine, ::state_machine::transaction::TransactionItem);
@@ -3,25 +3,33 @@
 use std::marker::PhantomData;
 
 use medici_core::ctstack::EmptyStack;
-use medici_core::service::{EntityService, TriggerService};
+use medici_core::function::{EntityId, Identifiable};
+use medici_core::service::{EntityService, RngService, TriggerService, ZoneService};
 use medici_core::storage::TransactionStorage;
 
-use state_machine::config::SetupConfig;
+use state_machine::config::{DeckList, DeckRules, SetupConfig};
 use state_machine::machine::Machine;
 use state_machine::state::leaf::triggerable::Start;
 use state_machine::state::toplevel::Wait;
 use state_machine::transaction::Epsilon;
 
+use card::{default_cards, GAME_CARD_ID, PLAYER_CARD_ID};
+use entity::GAME_E_ID;
 use prototype::Game as GameProto;
 use prototype::Player as PlayerProto;
 use tag::EntityTags;
+use zone::ZoneKind;
 
 pub mod error {
     //! Definitions for all possible errors thrown when setting up a
     //! new state machine.
 
     use failure::Fail;
-    use medici_core::error::custom_type::OverflowError;
+    use medici_core::error::custom_type::{CardSetError, MissingCardError, OverflowError,
+                                          ZoneError};
+    use medici_core::function::CardId;
+
+    use lifecycle::error::SpawnError;
 
     #[derive(Debug, Fail)]
     /// Enumeration of possible errors when setting up a new game.
@@ -33,6 +41,80 @@ pub mod error {
         #[fail(display = "Maximum entity limit reached. Increase the allowed amount of entities!")]
         /// Thrown when max entities is overflown.
         EntityOverflow(#[fail(cause)] OverflowError),
+
+        #[fail(display = "A card necessary to setup the game is not registered")]
+        /// Thrown when a card used during setup is not registered.
+        MissingCard(#[fail(cause)] MissingCardError<CardId>),
+
+        #[fail(display = "The player card is identified by {:} instead of the player card id", _0)]
+        /// Thrown when the configured player card would not replace the default player card.
+        InvalidPlayerCard(CardId),
+
+        #[fail(display = "A card set could not be installed")]
+        /// Thrown when a card set collides with the installed cards.
+        CardSetCollision(#[fail(cause)] CardSetError<CardId>),
+
+        #[fail(display = "A card could not be put into it's zone")]
+        /// Thrown when the zone of a card can't hold it.
+        ZoneOverflow(#[fail(cause)] ZoneError),
+
+        #[fail(display = "Deck of player {:} holds {:} cards, expected at least {:} and at most {:}",
+               player, size, min, max)]
+        /// Thrown when a deck holds too few or too many cards.
+        DeckSize {
+            /// PlayerID of the deck owner.
+            player: usize,
+            /// Amount of cards within the deck.
+            size: usize,
+            /// Minimum amount of cards allowed.
+            min: usize,
+            /// Maximum amount of cards allowed.
+            max: usize,
+        },
+
+        #[fail(display = "Deck of player {:} holds {:} copies of {:}, at most {:} are allowed",
+               player, copies, card, max)]
+        /// Thrown when a deck holds too many copies of one card.
+        TooManyCopies {
+            /// PlayerID of the deck owner.
+            player: usize,
+            /// The copied card.
+            card: CardId,
+            /// Amount of copies within the deck.
+            copies: usize,
+            /// Maximum amount of copies allowed.
+            max: usize,
+        },
+
+        #[fail(display = "Deck of player {:} holds {:} from set {:}, which is not allowed",
+               player, card, set)]
+        /// Thrown when a deck holds a card from a set which is not allowed.
+        DisallowedSet {
+            /// PlayerID of the deck owner.
+            player: usize,
+            /// The card from the disallowed set.
+            card: CardId,
+            /// Identifier of the disallowed set.
+            set: u32,
+        },
+
+        #[fail(display = "Deck of player {:} holds banned card {:}", player, card)]
+        /// Thrown when a deck holds a banned card.
+        BannedCard {
+            /// PlayerID of the deck owner.
+            player: usize,
+            /// The banned card.
+            card: CardId,
+        },
+
+        #[fail(display = "Deck of player {:} holds {:}, which is not registered", player, card)]
+        /// Thrown when a deck holds a card which is not registered on the machine.
+        UnknownDeckCard {
+            /// PlayerID of the deck owner.
+            player: usize,
+            /// The unknown card.
+            card: CardId,
+        },
     }
 
     impl From<OverflowError> for SetupError {
@@ -40,11 +122,35 @@ pub mod error {
             SetupError::EntityOverflow(x)
         }
     }
+
+    impl From<SpawnError> for SetupError {
+        fn from(x: SpawnError) -> Self {
+            match x {
+                SpawnError::MissingCard(e) => SetupError::MissingCard(e),
+                SpawnError::EntityOverflow(e) => SetupError::EntityOverflow(e),
+            }
+        }
+    }
+
+    impl From<CardSetError<CardId>> for SetupError {
+        fn from(x: CardSetError<CardId>) -> Self {
+            SetupError::CardSetCollision(x)
+        }
+    }
+
+    impl From<ZoneError> for SetupError {
+        fn from(x: ZoneError) -> Self {
+            SetupError::ZoneOverflow(x)
+        }
+    }
 }
 use self::error::SetupError;
 
 impl Machine<Wait<Start>, EmptyStack> {
     /// Creates a new state machine ready to be started.
+    ///
+    /// The deck of each player is validated against the configured rules before it's
+    /// cards are shuffled into the deck zone of that player.
     pub fn new(cfg: &SetupConfig) -> Result<Self, SetupError> {
         let mut game = Self {
             state: PhantomData,
@@ -54,38 +160,146 @@ impl Machine<Wait<Start>, EmptyStack> {
             transactions: TransactionStorage::new(),
             entities: EntityService::new(cfg.max_entities),
             triggers: TriggerService::new(),
+            zones: ZoneService::new(),
+            cards: default_cards(),
+            rng: RngService::new(cfg.seed),
+            players: vec![],
         };
+        game.triggers.set_recursion_limit(cfg.max_trigger_recursion);
+        game.zones.set_capacity(ZoneKind::Hand, cfg.max_hand_size);
+        game.zones.set_capacity(ZoneKind::Board, cfg.max_board_size);
+        if let Some(ref card) = cfg.player_card {
+            if card.id() != PLAYER_CARD_ID {
+                return Err(SetupError::InvalidPlayerCard(card.id()));
+            }
+            game.cards
+                .replace_card(card.clone())
+                .map_err(SetupError::MissingCard)?;
+        }
+        for set in cfg.card_sets.iter() {
+            game.cards.install_set(&**set)?;
+        }
         game = game.setup_game(cfg)?;
         game = game.setup_players(cfg)?;
         Ok(game)
     }
 
     fn setup_game(mut self, cfg: &SetupConfig) -> Result<Self, SetupError> {
-        let game_entity = self.entities.new_entity()?;
-        game_entity.add_proto::<GameProto>();
-        // Count the amount of players provided by the config.
-        // MaxPlayers is used as tag name because the game starts with this amount.
-        // Within last-man-standing games the amount of 'alive' players is equal to or
-        // smaller than the MaxPlayers amount.
-        let num_players = cfg.player_names.iter().filter(|p| p.is_some()).count();
-        game_entity.set_value(EntityTags::MaxPlayers, num_players as u32);
+        // The game entity is the first entity and owns itself.
+        let game_id = {
+            let game_entity = self.spawn_from_card(GAME_CARD_ID, GAME_E_ID)?;
+            game_entity.add_proto::<GameProto>();
+            // Count the amount of players provided by the config.
+            // MaxPlayers is used as tag name because the game starts with this amount.
+            // Within last-man-standing games the amount of 'alive' players is equal to or
+            // smaller than the MaxPlayers amount.
+            let num_players = cfg.player_names.iter().filter(|p| p.is_some()).count();
+            game_entity.set_value(EntityTags::MaxPlayers, num_players as u32);
+            game_entity.id()
+        };
+        // The game entity is in play for the whole game.
+        self.activate_entity(game_id);
         // TODO; Other game setup steps
         Ok(self)
     }
 
     fn setup_players(mut self, cfg: &SetupConfig) -> Result<Self, SetupError> {
-        let players_names = cfg.player_names
+        let players: Vec<_> = cfg.player_names
             .iter()
-            .filter(|e| e.is_some())
-            .map(|n| n.clone());
-        for player_name in players_names {
-            let mut player_entity = self.entities.new_entity()?;
-            // Add name of player to entity.
-            player_entity.human_readable = player_name;
-            player_entity.add_proto::<PlayerProto>();
+            .zip(cfg.decks.iter())
+            .filter(|&(name, _)| name.is_some())
+            .collect();
+        // Decks are validated before any player is created.
+        for (idx, &(_, deck)) in players.iter().enumerate() {
+            if let Some(ref deck) = *deck {
+                // PlayerIDs start counting at 1.
+                self.validate_deck(idx + 1, deck, &cfg.deck_rules)?;
+            }
+        }
+
+        let mut player_ids = vec![];
+        for &(player_name, _) in players.iter() {
+            let player_id = {
+                let player_entity = self.spawn_from_card(PLAYER_CARD_ID, GAME_E_ID)?;
+                // Add name of player to entity.
+                player_entity.human_readable = player_name.clone();
+                player_entity.add_proto::<PlayerProto>();
+                player_entity.id()
+            };
+            // Players are in play for the whole game.
+            self.activate_entity(player_id);
+            player_ids.push(player_id);
 
             // TODO; Other player setup steps
         }
+
+        // Deck cards are created after all players, so players receive consecutive ids.
+        for (&player_id, &(_, deck)) in player_ids.iter().zip(players.iter()) {
+            if let Some(ref deck) = *deck {
+                self.setup_deck(player_id, deck)?;
+            }
+        }
+        self.players = player_ids;
         Ok(self)
     }
+
+    fn validate_deck(
+        &self,
+        player: usize,
+        deck: &DeckList,
+        rules: &DeckRules,
+    ) -> Result<(), SetupError> {
+        for &(card, _) in deck.cards.iter() {
+            if self.cards.get_card(card).is_err() {
+                return Err(SetupError::UnknownDeckCard { player, card });
+            }
+            if rules.banned_cards.contains(&card) {
+                return Err(SetupError::BannedCard { player, card });
+            }
+            if let Some(ref sets) = rules.allowed_sets {
+                if !sets.contains(&card.set()) {
+                    let set = card.set();
+                    return Err(SetupError::DisallowedSet { player, card, set });
+                }
+            }
+            let copies = deck.copies(card);
+            if copies > rules.max_copies {
+                let max = rules.max_copies;
+                return Err(SetupError::TooManyCopies {
+                    player,
+                    card,
+                    copies,
+                    max,
+                });
+            }
+        }
+
+        let size = deck.size();
+        if size < rules.min_size || size > rules.max_size {
+            return Err(SetupError::DeckSize {
+                player,
+                size,
+                min: rules.min_size,
+                max: rules.max_size,
+            });
+        }
+        Ok(())
+    }
+
+    fn setup_deck(&mut self, player: EntityId, deck: &DeckList) -> Result<(), SetupError> {
+        let mut entity_ids = vec![];
+        for &(card, count) in deck.cards.iter() {
+            for _ in 0..count {
+                // Cards within the deck are not in play, their triggers are activated
+                // once they enter the board.
+                entity_ids.push(self.spawn_from_card(card, player)?.id());
+            }
+        }
+        // The draw order only depends on the seed of the machine.
+        self.rng.shuffle(&mut entity_ids);
+        for entity_id in entity_ids {
+            self.zones.insert(entity_id, player, ZoneKind::Deck, None)?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,178 @@
+//! Module containing types to persist and reload a state machine.
+//!
+//! Only machines in a [`Wait`] state can be persisted, because this is the only moment
+//! where the machine is not processing any effect.
+//! Enable the `serde-support` feature to (de)serialize [`MachineSnapshot`].
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+
+use medici_core::ctstack::EmptyStack;
+use medici_core::error::custom_type::TransactionUnpackError;
+use medici_core::function::{EntityId, State, StateContainer};
+use medici_core::marker;
+use medici_core::service::trigger::TriggerSnapshot;
+use medici_core::service::{CardService, EntityService, RngService, TriggerService,
+                           ZoneService};
+use medici_core::storage::TransactionStorage;
+use medici_core::transaction::{pack_transaction, unpack_transaction};
+
+use card::Card;
+use entity::Entity;
+use state_machine::machine::Machine;
+use state_machine::state::prelude::*;
+use state_machine::transaction::TransactionItem;
+use zone::ZoneKind;
+
+pub mod error {
+    //! Definitions for all possible errors thrown when persisting or reloading
+    //! a state machine.
+
+    use failure::Fail;
+    use medici_core::error::custom_type::{MissingCardError, MissingTriggerError,
+                                          TransactionUnpackError, TriggerFail};
+    use medici_core::function::CardId;
+
+    #[derive(Debug, Fail)]
+    /// Enumeration of possible errors when building or restoring snapshots.
+    pub enum SnapshotError {
+        #[fail(display = "The snapshot holds state `{:}`, expected `{:}`", factual, expected)]
+        /// Thrown when the snapshot was taken in another state than the one requested.
+        StateMismatch {
+            /// Name of the requested state.
+            expected: String,
+            /// Name of the state stored within the snapshot.
+            factual: String,
+        },
+
+        #[fail(display = "A trigger could not be persisted")]
+        /// Thrown when a trigger callback is not known to the registry.
+        UnregisteredTrigger(#[fail(cause)] TriggerFail),
+
+        #[fail(display = "A trigger could not be restored")]
+        /// Thrown when a persisted trigger name is not known to the registry.
+        MissingTrigger(#[fail(cause)] MissingTriggerError<String>),
+
+        #[fail(display = "The transaction of the snapshot is invalid")]
+        /// Thrown when the stored transaction doesn't match the requested state.
+        InvalidTransaction(#[fail(cause)] TransactionUnpackError),
+
+        #[fail(display = "An entity was spawned from an unknown card")]
+        /// Thrown when the card of a persisted entity is not known to the provided
+        /// card service.
+        MissingCard(#[fail(cause)] MissingCardError<CardId>),
+    }
+
+    impl From<TriggerFail> for SnapshotError {
+        fn from(x: TriggerFail) -> Self {
+            SnapshotError::UnregisteredTrigger(x)
+        }
+    }
+
+    impl From<MissingTriggerError<String>> for SnapshotError {
+        fn from(x: MissingTriggerError<String>) -> Self {
+            SnapshotError::MissingTrigger(x)
+        }
+    }
+
+    impl From<TransactionUnpackError> for SnapshotError {
+        fn from(x: TransactionUnpackError) -> Self {
+            SnapshotError::InvalidTransaction(x)
+        }
+    }
+
+    impl From<MissingCardError<CardId>> for SnapshotError {
+        fn from(x: MissingCardError<CardId>) -> Self {
+            SnapshotError::MissingCard(x)
+        }
+    }
+}
+use self::error::SnapshotError;
+
+/// Registry of all trigger callbacks which can be persisted for our machine.
+pub type TriggerRegistry = ::medici_core::service::TriggerRegistry<TimingItem, TriggerItem>;
+
+/// Portable format of a [`Machine`] in a [`Wait`] state.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct MachineSnapshot {
+    /// Name of the state the machine was in, eg `Wait<Input>`.
+    pub state: String,
+    /// The transaction of the state the machine was in.
+    pub transaction: TransactionItem,
+    /// All entities of the machine.
+    pub entities: EntityService<Entity>,
+    /// All zones of the machine.
+    pub zones: ZoneService<ZoneKind>,
+    /// The random number generator of the machine.
+    pub rng: RngService,
+    /// All stored transactions of the machine.
+    pub transactions: TransactionStorage<TransactionItem>,
+    /// All triggers of the machine, by their registered name.
+    pub triggers: TriggerSnapshot,
+    /// Identifiers of the player entities, ordered by PlayerID.
+    pub players: Vec<EntityId>,
+}
+
+impl<W> Machine<Wait<W>, EmptyStack>
+where
+    W: marker::Waitable + State + Send,
+    W::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+{
+    /// Builds a portable snapshot of this machine.
+    ///
+    /// Each registered trigger MUST have it's callback registered within the provided
+    /// registry.
+    pub fn snapshot(&self, registry: &TriggerRegistry) -> Result<MachineSnapshot, SnapshotError> {
+        Ok(MachineSnapshot {
+            state: self.state_name(),
+            transaction: pack_transaction(self.transaction),
+            entities: self.entities.clone(),
+            zones: self.zones.clone(),
+            rng: self.rng.clone(),
+            transactions: self.transactions.clone(),
+            triggers: self.triggers.snapshot(registry)?,
+            players: self.players.clone(),
+        })
+    }
+
+    /// Rebuilds a machine from the provided snapshot.
+    ///
+    /// The trigger callbacks are re-bound through the provided registry.
+    /// Cards are not part of the snapshot, the provided card service MUST hold each
+    /// card the persisted entities were spawned from. Pass the card service of the
+    /// persisted machine, or a service with the same card sets installed.
+    pub fn restore(
+        snapshot: MachineSnapshot,
+        cards: CardService<Card>,
+        registry: &TriggerRegistry,
+    ) -> Result<Self, SnapshotError> {
+        let expected = <Wait<W> as State>::name();
+        if snapshot.state != expected {
+            return Err(SnapshotError::StateMismatch {
+                expected,
+                factual: snapshot.state,
+            });
+        }
+
+        for card_id in snapshot.entities.iter().filter_map(|e| e.card_id) {
+            cards.get_card(card_id)?;
+        }
+
+        let transaction = unpack_transaction(snapshot.transaction)?;
+        let triggers = TriggerService::restore(&snapshot.triggers, registry)?;
+        Ok(Self {
+            state: PhantomData,
+            history: PhantomData,
+            transaction,
+            triggers,
+            entities: snapshot.entities,
+            cards,
+            zones: snapshot.zones,
+            rng: snapshot.rng,
+            transactions: snapshot.transactions,
+            players: snapshot.players,
+        })
+    }
+}
@@ -2,6 +2,11 @@
 
 use std::default::Default;
 
+use medici_core::function::{CardId, CardSet};
+use medici_core::service::trigger::DEFAULT_RECURSION_LIMIT;
+
+use card::Card;
+
 /// Constant defining how much memory at minimum MUST be (statically) allocated
 /// to support all players that joined the game.
 pub const MAX_PLAYERS: usize = 5;
@@ -20,6 +25,32 @@ pub struct SetupConfig {
     pub player_names: [Option<String>; MAX_PLAYERS],
     /// Maximum amount of entities to be stored inside this machine.
     pub max_entities: usize,
+    /// Maximum amount of trigger chains which can be nested.
+    ///
+    /// Triggers which keep causing each other will fail once this limit is reached.
+    pub max_trigger_recursion: usize,
+    /// Maximum amount of cards each player can hold in hand.
+    pub max_hand_size: usize,
+    /// Maximum amount of cards each player can have on the board.
+    pub max_board_size: usize,
+    /// Card each player entity is built from.
+    ///
+    /// This card replaces the default player card and MUST be identified by
+    /// [`PLAYER_CARD_ID`]. The default player card is used if this is [`None`].
+    pub player_card: Option<Card>,
+    /// Sets of cards which are installed into the machine, see [`CardService::install_set`].
+    pub card_sets: Vec<Box<CardSet<Card = Card>>>,
+    /// Deck for each player.
+    ///
+    /// Each deck belongs to the player defined at the same index within `player_names`.
+    /// The cards of each deck are shuffled into the deck zone of their player.
+    pub decks: [Option<DeckList>; MAX_PLAYERS],
+    /// Rules each deck must follow.
+    pub deck_rules: DeckRules,
+    /// Seed for all random numbers generated by the machine.
+    ///
+    /// Games setup with the same seed, which receive the same actions, are identical.
+    pub seed: u64,
 }
 
 impl Default for SetupConfig {
@@ -32,6 +63,82 @@ impl Default for SetupConfig {
         SetupConfig {
             player_names,
             max_entities: usize::max_value(),
+            max_trigger_recursion: DEFAULT_RECURSION_LIMIT,
+            max_hand_size: 10,
+            max_board_size: 7,
+            player_card: None,
+            card_sets: vec![],
+            decks: Default::default(),
+            deck_rules: Default::default(),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// List of the cards within a deck.
+pub struct DeckList {
+    /// Each card of the deck, together with the amount of copies.
+    ///
+    /// The order of this list is not kept, decks are shuffled at setup. The order
+    /// of the cards within the deck only depends on [`SetupConfig::seed`].
+    pub cards: Vec<(CardId, usize)>,
+}
+
+impl DeckList {
+    /// Creates a new, empty, deck list.
+    pub fn new() -> Self {
+        Self { cards: vec![] }
+    }
+
+    /// Adds the provided amount of copies of a card to this list.
+    pub fn with(mut self, card: CardId, count: usize) -> Self {
+        self.cards.push((card, count));
+        self
+    }
+
+    /// Returns the total amount of cards within this list.
+    pub fn size(&self) -> usize {
+        self.cards.iter().map(|&(_, count)| count).sum()
+    }
+
+    /// Returns the amount of copies of the provided card within this list.
+    pub fn copies(&self, card: CardId) -> usize {
+        self.cards
+            .iter()
+            .filter(|&&(id, _)| id == card)
+            .map(|&(_, count)| count)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Rules each [`DeckList`] is validated against when setting up a new [`Machine`].
+///
+/// The default rules accept any deck built from registered cards.
+pub struct DeckRules {
+    /// Minimum amount of cards within a deck.
+    pub min_size: usize,
+    /// Maximum amount of cards within a deck.
+    pub max_size: usize,
+    /// Maximum amount of copies of one card within a deck.
+    pub max_copies: usize,
+    /// Identifiers of the card sets decks can be built from.
+    ///
+    /// Cards from all sets are allowed if this is [`None`].
+    pub allowed_sets: Option<Vec<u32>>,
+    /// Cards which are not allowed within decks.
+    pub banned_cards: Vec<CardId>,
+}
+
+impl Default for DeckRules {
+    fn default() -> Self {
+        DeckRules {
+            min_size: 0,
+            max_size: usize::max_value(),
+            max_copies: usize::max_value(),
+            allowed_sets: None,
+            banned_cards: vec![],
         }
     }
 }
@@ -3,15 +3,20 @@
 use std::marker::PhantomData;
 
 use medici_core::ctstack::CTStack;
-use medici_core::function::{ServiceCompliance, State, StateContainer};
+use medici_core::function::{EntityId, State};
 use medici_core::marker;
-use medici_core::service::{EntityService, TriggerService};
+use medici_core::service::{CardService, EntityService, RngService, TriggerOrderContext,
+                           TriggerService, ZoneService};
 use medici_core::storage::TransactionStorage;
+use medici_derive::StateMachine;
 
 use state_machine::state::prelude::*;
 use state_machine::transaction::TransactionItem;
 
-use entity::Entity;
+use card::Card;
+use entity::{Entity, GAME_E_ID};
+use tag::EntityTags;
+use zone::ZoneKind;
 
 /// The state machine.
 ///
@@ -19,13 +24,16 @@ use entity::Entity;
 /// use case.
 /// Each state machine MUST have a `state`, `history` and `transaction` field AT
 /// MINIMUM.
+/// All other fields are moved into the next machine on each transition, fields
+/// marked with `#[service]` are accessible through [`ServiceCompliance`].
 ///
 /// # Safety
 /// Fields `state` and `history` are not accessible because altering them would cause
 /// the state transition and -history system to fall apart.
 /// Any code that has valid reason to alter these fields should be defined within this
 /// crate.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, StateMachine)]
+#[state_machine(timing = "TimingItem", trigger = "TriggerItem")]
 pub struct Machine<X, CTS>
 where
     X: marker::TopLevel + State + Send,
@@ -46,49 +54,60 @@ where
 
     /* Optionals */
     /// Object for manipulating [`Trigger`]s.
+    #[service]
     pub triggers: TriggerService<TimingItem, TriggerItem>,
     /// Object for manipulating [`Entity`]s.
+    #[service]
     pub entities: EntityService<Entity>,
+    /// Object holding all [`Card`]s entities can be built from.
+    #[service]
+    pub cards: CardService<Card>,
+    /// Object for manipulating the zones of each player.
+    #[service]
+    pub zones: ZoneService<ZoneKind>,
+    /// Object generating random numbers, seeded from [`SetupConfig::seed`].
+    #[service]
+    pub rng: RngService,
     /// Storage object allowing [`PushdownInto`] and [`PullupInto`] to store
     /// the [`Transaction`] objects for each state to be re-used.
+    #[service]
     pub transactions: TransactionStorage<TransactionItem>,
-}
 
-impl<X, CTS> StateContainer for Machine<X, CTS>
-where
-    X: marker::TopLevel + State + Send,
-    CTS: CTStack + Send,
-{
-    type State = X;
-    type TransitionRecord = CTS;
-    type TimingEnum = TimingItem;
-    type TriggerEnum = TriggerItem;
+    /* Game */
+    /// Identifiers of the player entities, ordered by PlayerID.
+    ///
+    /// This field is filled when setting up the machine and is not accessible so
+    /// the PlayerID of each player stays fixed, see [`Machine::players`].
+    pub(crate) players: Vec<EntityId>,
 }
 
-impl<X, CTS> ServiceCompliance<TriggerService<TimingItem, TriggerItem>> for Machine<X, CTS>
+impl<X, CTS> Machine<X, CTS>
 where
     X: marker::TopLevel + State + Send,
     CTS: CTStack + Send,
 {
-    fn get(&self) -> &TriggerService<TimingItem, TriggerItem> {
-        &self.triggers
-    }
-
-    fn get_mut(&mut self) -> &mut TriggerService<TimingItem, TriggerItem> {
-        &mut self.triggers
+    /// Returns the identifiers of all player entities, ordered by PlayerID.
+    ///
+    /// PlayerIDs start counting at 1, so the player with PlayerID 1 is the first element.
+    pub fn players(&self) -> &[EntityId] {
+        &self.players
     }
 }
 
-impl<X, CTS> ServiceCompliance<EntityService<Entity>> for Machine<X, CTS>
+impl<X, CTS> TriggerOrderContext for Machine<X, CTS>
 where
     X: marker::TopLevel + State + Send,
     CTS: CTStack + Send,
 {
-    fn get(&self) -> &EntityService<Entity> {
-        &self.entities
+    fn current_player(&self) -> Option<EntityId> {
+        let game = self.entities.get(GAME_E_ID).ok()?;
+        let player_ord = game.get_value(&EntityTags::CurrentPlayerOrd).ok()?;
+        // The ordinal starts counting at 1.
+        let player_idx = (player_ord as usize).checked_sub(1)?;
+        self.players.get(player_idx).cloned()
     }
 
-    fn get_mut(&mut self) -> &mut EntityService<Entity> {
-        &mut self.entities
+    fn controller_of(&self, entity: EntityId) -> Option<EntityId> {
+        self.entities.get(entity).ok().and_then(|e| e.owner)
     }
 }
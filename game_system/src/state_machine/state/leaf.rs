@@ -5,12 +5,12 @@
 
 use value_from_type_macros::value_from_type;
 
-use medici_core::function::State;
+use medici_derive::State;
 use medici_core::marker;
 /// Export the prefab timing items.
 pub use medici_core::prefab::timing::{Peri, Post, Pre, TimingItem};
 
-use state_machine::transaction::Epsilon;
+use state_machine::transaction::{self, Epsilon};
 
 pub mod triggerable {
     #![value_from_type(TriggerItem)]
@@ -29,55 +29,60 @@ pub mod triggerable {
     impl marker::TriggerEnumerator for TriggerItem {}
 
     /// Wait condition state until the game has been started.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct Start();
-    impl State for Start {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Waitable for Start {}
     impl marker::Triggerable for Start {}
     impl marker::Actionable for Start {}
 
     /// Wait condition state until the user has provided input.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct Input();
-    impl State for Input {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Waitable for Input {}
 
     /// Action condition state until the user has provided input.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct EndTurn();
-    impl State for EndTurn {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Actionable for EndTurn {}
     impl marker::Triggerable for EndTurn {}
 
     /// Action condition state indicating a card will be played.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct PlayCard();
-    impl State for PlayCard {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Actionable for PlayCard {}
     impl marker::Triggerable for PlayCard {}
 
     /// Action condition state indicating an attack will commence.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct Attack();
-    impl State for Attack {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Actionable for Attack {}
     impl marker::Triggerable for Attack {}
 
     /// Trigger condition for taken damage.
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "Epsilon")] // TODO
     pub struct Damage();
-    impl State for Damage {
-        type Transaction = Epsilon; // TODO
-    }
     impl marker::Triggerable for Damage {}
+
+    /// Trigger condition for entities which died.
+    ///
+    /// This triggerable is only used within [`DeathEffect`], the dying entity
+    /// is found within the transaction.
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "transaction::Death")]
+    pub struct Death();
+    impl marker::Triggerable for Death {}
+
+    /// Trigger condition for entities which moved between zones.
+    ///
+    /// The moved entity is found within the transaction.
+    #[derive(Debug, Clone, State)]
+    #[state(transaction = "transaction::ZoneChanged")]
+    pub struct ZoneChanged();
+    impl marker::Triggerable for ZoneChanged {}
 }
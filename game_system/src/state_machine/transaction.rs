@@ -1,9 +1,11 @@
 //! Types used to convey transition related information.
 
-use std::convert::TryFrom;
-
-use medici_core::error::custom_type::TransactionUnpackError;
+use medici_core::function::EntityId;
 use medici_core::marker;
+use medici_core::storage::ZonePosition;
+use medici_derive::TransactionContainer;
+
+use zone::ZoneKind;
 
 // Epsilon is re-exported here so implementers can do
 // 'use transaction::*' and have both the medici_core transactions
@@ -12,33 +14,39 @@ pub use medici_core::prefab::transaction::Epsilon;
 
 /// Collection of known Transaction structures wrapped into a Sized
 /// item.
-#[derive(Debug, Clone)]
+///
+/// Conversions between this container and the wrapped transactions are
+/// derived, see [`TransactionContainer`].
+#[derive(Debug, Clone, TransactionContainer)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub enum TransactionItem {
     /// See [`Epsilon`]
     Epsilon(Epsilon),
+    /// See [`Death`]
+    Death(Death),
+    /// See [`ZoneChanged`]
+    ZoneChanged(ZoneChanged),
 }
 
-impl marker::TransactionContainer for TransactionItem {}
-
-impl From<Epsilon> for TransactionItem {
-    fn from(x: Epsilon) -> Self {
-        TransactionItem::Epsilon(x)
-    }
+/// Transaction carrying the entity which is processed within
+/// the [`DeathEffect`] states.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct Death {
+    /// The entity which died.
+    pub entity: EntityId,
 }
-
-impl TryFrom<TransactionItem> for Epsilon {
-    type Error = TransactionUnpackError;
-
-    fn try_from(tc: TransactionItem) -> Result<Self, Self::Error> {
-        match tc {
-            TransactionItem::Epsilon(x) => Ok(x),
-            /*
-            e @ _ => {
-                let expected = stringify!(TransactionItem::Epsilon);
-                let factual = format!("{:?}", e);
-                Err((expected, factual).into())
-            }
-            */
-        }
-    }
+impl marker::Transaction for Death {}
+
+/// Transaction carrying the entity which moved between zones.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct ZoneChanged {
+    /// The moved entity.
+    pub entity: EntityId,
+    /// Location of the entity before moving.
+    pub from: ZonePosition<ZoneKind>,
+    /// Location of the entity after moving.
+    pub into: ZonePosition<ZoneKind>,
 }
+impl marker::Transaction for ZoneChanged {}
@@ -1,7 +1,12 @@
 //! Module containing the property tags which can be attributed to [`Entity`]s
 //! and other game-objects.
 
+use std::str::FromStr;
+
+use failure::Fail;
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Enumeration of all entity property keys.
 pub enum EntityTags {
     /* GAME META TAGS */
@@ -24,3 +29,26 @@ pub enum EntityTags {
     /// Amount of damage an entity has taken.
     Damage,
 }
+
+/// Error thrown when a property name doesn't match any [`EntityTags`] variant.
+#[derive(Debug, Fail)]
+#[fail(display = "The property `{:}` is not a known entity tag", _0)]
+pub struct UnknownTagError(pub String);
+
+impl FromStr for EntityTags {
+    type Err = UnknownTagError;
+
+    /// Parses the name of a tag, eg `Health`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "MaxPlayers" => Ok(EntityTags::MaxPlayers),
+            "CurrentPlayerOrd" => Ok(EntityTags::CurrentPlayerOrd),
+            "RemainingTurns" => Ok(EntityTags::RemainingTurns),
+            "StartHandSize" => Ok(EntityTags::StartHandSize),
+            "Attack" => Ok(EntityTags::Attack),
+            "Health" => Ok(EntityTags::Health),
+            "Damage" => Ok(EntityTags::Damage),
+            _ => Err(UnknownTagError(s.to_string())),
+        }
+    }
+}
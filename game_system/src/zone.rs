@@ -0,0 +1,155 @@
+//! Module containing the zones of our game.
+//!
+//! Each player owns one zone of each [`ZoneKind`]. Entities moved by the methods
+//! within this module cause a [`ZoneChanged`] trigger chain, so rules can react
+//! to these moves.
+//! Triggers of entities entering the board are activated, triggers of entities
+//! leaving the board are deactivated.
+
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+use medici_core::ctstack::CTStack;
+use medici_core::error::custom_type::{TransactionUnpackError, ZoneError};
+use medici_core::error::{ErrorKind, FrontendErrorExt, MachineError};
+use medici_core::function::{EntityId, State};
+use medici_core::marker;
+use medici_core::storage::ZoneMove;
+
+use runtime::exec_recurse;
+use state_machine::machine::Machine;
+use state_machine::state::leaf::triggerable::ZoneChanged;
+use state_machine::state::toplevel::Trigger;
+use state_machine::transaction::{self, TransactionItem};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Enumeration of all zones each player owns.
+pub enum ZoneKind {
+    /// Cards which can be drawn, the top card is drawn first.
+    Deck,
+    /// Cards which can be played.
+    Hand,
+    /// Cards in play.
+    Board,
+    /// Cards which were destroyed.
+    Graveyard,
+}
+
+/// Moves the top card of the deck of the provided player into the hand of that player.
+pub fn draw_card<TM, TR, CTS>(
+    machine: Machine<Trigger<TM, TR>, CTS>,
+    player: EntityId,
+) -> Result<Machine<Trigger<TM, TR>, CTS>, MachineError>
+where
+    TM: marker::Timing + State + Send + 'static,
+    TR: marker::Triggerable + State + Send + 'static,
+    TR::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+    Machine<Trigger<TM, TR>, CTS>: Debug + Send,
+{
+    let top = machine.zones.top(player, ZoneKind::Deck);
+    match top {
+        Some(entity) => move_entity(machine, entity, player, ZoneKind::Hand, None),
+        None => Err(ZoneError::Empty.infuse(ErrorKind::ConstraintError, || machine)),
+    }
+}
+
+/// Moves the provided card from the hand of it's owner onto the board of that owner,
+/// at the provided index.
+///
+/// # Errors
+/// [`ZoneError::InvalidSource`] is returned when the card is not within the hand of
+/// it's owner.
+pub fn play_card<TM, TR, CTS>(
+    machine: Machine<Trigger<TM, TR>, CTS>,
+    entity: EntityId,
+    index: Option<usize>,
+) -> Result<Machine<Trigger<TM, TR>, CTS>, MachineError>
+where
+    TM: marker::Timing + State + Send + 'static,
+    TR: marker::Triggerable + State + Send + 'static,
+    TR::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+    Machine<Trigger<TM, TR>, CTS>: Debug + Send,
+{
+    let position = machine.zones.position_of(entity);
+    let owner = machine.entities.get(entity).ok().and_then(|e| e.owner);
+    match position {
+        Some(p) if p.zone == ZoneKind::Hand && owner == Some(p.owner) => {
+            move_entity(machine, entity, p.owner, ZoneKind::Board, index)
+        }
+        Some(_) => {
+            Err(ZoneError::InvalidSource(entity).infuse(ErrorKind::ConstraintError, || machine))
+        }
+        None => {
+            Err(ZoneError::MissingEntity(entity).infuse(ErrorKind::ConstraintError, || machine))
+        }
+    }
+}
+
+/// Moves the provided card into the graveyard of it's owner.
+pub fn destroy_card<TM, TR, CTS>(
+    machine: Machine<Trigger<TM, TR>, CTS>,
+    entity: EntityId,
+) -> Result<Machine<Trigger<TM, TR>, CTS>, MachineError>
+where
+    TM: marker::Timing + State + Send + 'static,
+    TR: marker::Triggerable + State + Send + 'static,
+    TR::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+    Machine<Trigger<TM, TR>, CTS>: Debug + Send,
+{
+    let position = machine.zones.position_of(entity);
+    match position {
+        Some(p) => move_entity(machine, entity, p.owner, ZoneKind::Graveyard, None),
+        None => {
+            Err(ZoneError::MissingEntity(entity).infuse(ErrorKind::ConstraintError, || machine))
+        }
+    }
+}
+
+/// Moves the provided entity into the requested zone and executes the [`ZoneChanged`]
+/// triggers for that move.
+pub fn move_entity<TM, TR, CTS>(
+    mut machine: Machine<Trigger<TM, TR>, CTS>,
+    entity: EntityId,
+    owner: EntityId,
+    kind: ZoneKind,
+    index: Option<usize>,
+) -> Result<Machine<Trigger<TM, TR>, CTS>, MachineError>
+where
+    TM: marker::Timing + State + Send + 'static,
+    TR: marker::Triggerable + State + Send + 'static,
+    TR::Transaction:
+        Into<TransactionItem> + TryFrom<TransactionItem, Error = TransactionUnpackError>,
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+    Machine<Trigger<TM, TR>, CTS>: Debug + Send,
+{
+    let change = machine.zones.move_entity(entity, owner, kind, index);
+    let change = match change {
+        Ok(v) => v,
+        Err(e) => return Err(e.infuse(ErrorKind::ConstraintError, || machine)),
+    };
+
+    if change.from.zone == ZoneKind::Board && change.into.zone != ZoneKind::Board {
+        machine.deactivate_entity(entity);
+    } else if change.from.zone != ZoneKind::Board && change.into.zone == ZoneKind::Board {
+        machine.activate_entity(entity);
+    }
+    let transaction = transaction::ZoneChanged::from(change);
+    exec_recurse::<ZoneChanged, _, _, _, _, _, _>(machine, transaction)
+}
+
+impl From<ZoneMove<ZoneKind>> for transaction::ZoneChanged {
+    fn from(x: ZoneMove<ZoneKind>) -> Self {
+        transaction::ZoneChanged {
+            entity: x.entity,
+            from: x.from,
+            into: x.into,
+        }
+    }
+}
@@ -5,13 +5,14 @@ extern crate game_system;
 
 use std::fmt::Debug;
 
+use game_system::prelude::error::custom_type::CardSetError;
 use game_system::prelude::*;
 
 #[test]
 /// Creates a new card with added triggers.
 fn card_creation() {
     let mut c = Card::new_with_id(CardId::new(5, 2));
-    c.name = "Game card";
+    c.name = "Game card".into();
     c.set_value(EntityTags::Attack, 5);
     c.set_value(EntityTags::Health, 10);
 
@@ -83,3 +84,61 @@ fn macro_impl() {
         }
     };
 }
+
+#[derive(Debug)]
+struct WizardSet;
+
+impl CardSetTrait for WizardSet {
+    type Card = Card;
+
+    fn set_id(&self) -> u32 {
+        7
+    }
+
+    fn name(&self) -> &'static str {
+        "Wizards"
+    }
+
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn cards(&self) -> Vec<Card> {
+        (1..4)
+            .map(|id| {
+                card_impl!{
+                    ID = CardId::new(7, id);
+                    NAME = "Wizard";
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+/// Cards of installed sets can be looked up by their set.
+fn card_set_install() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.cards.install_set(&WizardSet).unwrap();
+    {
+        let info = machine.cards.set(7).unwrap();
+        assert_eq!((info.name, info.version), ("Wizards", 2));
+    }
+    let ids: Vec<CardId> = machine.cards.cards_in_set(7).map(|c| c.id()).collect();
+    assert_eq!(
+        ids,
+        vec![CardId::new(7, 1), CardId::new(7, 2), CardId::new(7, 3)]
+    );
+    assert_eq!(
+        machine
+            .cards
+            .cards()
+            .filter(|c| c.id().set() == 7)
+            .count(),
+        3
+    );
+
+    let error = machine.cards.install_set(&WizardSet).err();
+    assert_eq!(error, Some(CardSetError::DuplicateSet(7)));
+    assert_eq!(machine.cards.sets().count(), 1);
+}
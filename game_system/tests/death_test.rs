@@ -0,0 +1,183 @@
+extern crate game_system;
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use game_system::prelude::prototype::Player;
+use game_system::prelude::*;
+use game_system::re_export::function::State;
+use game_system::re_export::marker::TopLevel;
+use game_system::runtime::{exec_death_phase, exec_triggers};
+
+static DEATH_COUNT: AtomicUsize = AtomicUsize::new(0);
+static HEAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Each death damages all other entities with health.
+fn splash_on_death<CTS>(
+    mut x: Machine<DeathEffect<Peri, Death>, CTS>,
+) -> Result<Machine<DeathEffect<Peri, Death>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    DEATH_COUNT.fetch_add(1, Ordering::SeqCst);
+
+    let dying = x.transaction.entity;
+    let targets: Vec<EntityId> = x
+        .entities
+        .iter()
+        .filter(|e| e.id() != dying && e.get_value(&EntityTags::Health).is_ok())
+        .map(|e| e.id())
+        .collect();
+    for id in targets {
+        let entity = x.entities.get_mut(id).unwrap();
+        let damage = entity.get_value_default(&EntityTags::Damage);
+        entity.set_value(EntityTags::Damage, damage + 1);
+    }
+    Ok(x)
+}
+
+fn first_player<X, CTS>(machine: &Machine<X, CTS>) -> EntityId
+where
+    X: TopLevel + State + Send,
+    CTS: CTStack + Send,
+{
+    machine
+        .entities
+        .with_proto::<Player>()
+        .map(|e| e.id())
+        .next()
+        .unwrap()
+}
+
+/// Puts a new entity with the provided health onto the board of the first player.
+fn new_minion<X, CTS>(machine: &mut Machine<X, CTS>, health: u32) -> EntityId
+where
+    X: TopLevel + State + Send,
+    CTS: CTStack + Send,
+{
+    let player = first_player(machine);
+    let id = {
+        let entity = machine.entities.new_entity().unwrap();
+        entity.set_value(EntityTags::Health, health);
+        entity.id()
+    };
+    machine
+        .zones
+        .insert(id, player, ZoneKind::Board, None)
+        .unwrap();
+    id
+}
+
+#[test]
+/// Dead entities are removed after their death triggers executed, which can
+/// cause more entities to die.
+fn death_phase() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(splash_on_death::<AnyStack>);
+    let first = new_minion(&mut machine, 1);
+    let second = new_minion(&mut machine, 1);
+    let survivor = new_minion(&mut machine, 5);
+    machine
+        .entities
+        .get_mut(first)
+        .unwrap()
+        .set_value(EntityTags::Damage, 1);
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    let effect = exec_death_phase(effect).unwrap();
+    let action: Machine<Action<EndTurn>, _> = effect.pullup().unwrap();
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+
+    // The death of the first minion kills the second one.
+    assert_eq!(DEATH_COUNT.load(Ordering::SeqCst), 2);
+    assert!(machine.entities.get(first).is_err());
+    assert!(machine.entities.get(second).is_err());
+    let survivor = machine.entities.get(survivor).unwrap();
+    assert_eq!(survivor.get_value_default(&EntityTags::Damage), 2);
+    // Entities without health never die.
+    assert!(machine.entities.get(GAME_E_ID).is_ok());
+}
+
+/// Ending the turn kills every entity with health.
+fn kill_on_end_turn<CTS>(
+    mut x: Machine<Trigger<Peri, EndTurn>, CTS>,
+) -> Result<Machine<Trigger<Peri, EndTurn>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    for entity in x.entities.iter_mut() {
+        if let Ok(health) = entity.get_value(&EntityTags::Health) {
+            entity.set_value(EntityTags::Damage, health);
+        }
+    }
+    Ok(x)
+}
+
+/// Each dying entity is healed again.
+fn heal_on_death<CTS>(
+    mut x: Machine<DeathEffect<Post, Death>, CTS>,
+) -> Result<Machine<DeathEffect<Post, Death>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    HEAL_COUNT.fetch_add(1, Ordering::SeqCst);
+    let dying = x.transaction.entity;
+    x.entities
+        .get_mut(dying)
+        .unwrap()
+        .set_value(EntityTags::Damage, 0);
+    Ok(x)
+}
+
+#[test]
+/// Entities dying during an effect chain are processed before the chain ends,
+/// entities healed by their death triggers stay in play.
+fn death_after_effect() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(kill_on_end_turn::<AnyStack>);
+    let minion = new_minion(&mut machine, 2);
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    let mut effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+    assert!(effect.entities.get(minion).is_err());
+
+    effect.triggers.add_trigger(heal_on_death::<AnyStack>);
+    let survivor = new_minion(&mut effect, 2);
+    let effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+    assert_eq!(HEAL_COUNT.load(Ordering::SeqCst), 1);
+    let survivor = effect.entities.get(survivor).unwrap();
+    assert_eq!(survivor.get_value_default(&EntityTags::Damage), 0);
+}
+
+#[test]
+/// Players stay in play when their damage reaches their health, only entities on
+/// the board die.
+fn players_never_die() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let player = first_player(&machine);
+    let hand_card = machine.entities.new_entity().unwrap().id();
+    machine
+        .zones
+        .insert(hand_card, player, ZoneKind::Hand, None)
+        .unwrap();
+    for &id in &[player, hand_card] {
+        let entity = machine.entities.get_mut(id).unwrap();
+        entity.set_value(EntityTags::Health, 1);
+        entity.set_value(EntityTags::Damage, 1);
+    }
+    assert!(machine.dead_entities().is_empty());
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    let effect = exec_death_phase(effect).unwrap();
+    assert!(effect.entities.get(player).is_ok());
+    assert!(effect.entities.get(hand_card).is_ok());
+}
@@ -1,6 +1,7 @@
 extern crate game_system;
 
 use game_system::prelude::*;
+use game_system::setup::error::SetupError;
 
 #[test]
 #[should_panic]
@@ -44,3 +45,147 @@ fn config() {
         assert_eq!(player.human_readable, config.player_names[i]);
     }
 }
+
+#[derive(Debug)]
+struct DeckSet;
+
+impl CardSetTrait for DeckSet {
+    type Card = Card;
+
+    fn set_id(&self) -> u32 {
+        3
+    }
+
+    fn name(&self) -> &'static str {
+        "Deck cards"
+    }
+
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn cards(&self) -> Vec<Card> {
+        (1..4)
+            .map(|id| Card::new_with_id(CardId::new(3, id)))
+            .collect()
+    }
+}
+
+fn deck_config() -> SetupConfig {
+    let mut config: SetupConfig = Default::default();
+    config.card_sets.push(Box::new(DeckSet));
+    config.decks[0] = Some(
+        DeckList::new()
+            .with(CardId::new(3, 1), 2)
+            .with(CardId::new(3, 2), 1),
+    );
+    config.decks[1] = Some(DeckList::new().with(CardId::new(3, 3), 3));
+    config.deck_rules = DeckRules {
+        min_size: 3,
+        max_size: 3,
+        max_copies: 3,
+        allowed_sets: Some(vec![3]),
+        banned_cards: vec![],
+    };
+    config
+}
+
+#[test]
+/// The cards of each deck are put into the deck zone of their player.
+fn decks() {
+    let machine = Machine::new(&deck_config()).unwrap();
+    let first_deck = machine.zones.entities(1, ZoneKind::Deck);
+    let mut card_ids: Vec<CardId> = first_deck
+        .iter()
+        .map(|&e| machine.entities.get(e).unwrap().card_id.unwrap())
+        .collect();
+    // Decks are shuffled.
+    card_ids.sort_by_key(|c| (c.set(), c.ordinal()));
+    assert_eq!(
+        card_ids,
+        vec![CardId::new(3, 1), CardId::new(3, 1), CardId::new(3, 2)]
+    );
+    assert_eq!(machine.zones.entities(2, ZoneKind::Deck).len(), 3);
+    // Cards within the deck are not in play.
+    assert_eq!(
+        machine
+            .triggers
+            .retrieve_triggers_owned_by(first_deck[0])
+            .count(),
+        0
+    );
+}
+
+fn shuffled_deck(seed: u64) -> Vec<EntityId> {
+    let mut config: SetupConfig = Default::default();
+    config.card_sets.push(Box::new(DeckSet));
+    config.decks[0] = Some(
+        DeckList::new()
+            .with(CardId::new(3, 1), 3)
+            .with(CardId::new(3, 2), 3)
+            .with(CardId::new(3, 3), 3),
+    );
+    config.seed = seed;
+    let machine = Machine::new(&config).unwrap();
+    let deck = machine.zones.entities(1, ZoneKind::Deck).to_vec();
+    deck
+}
+
+#[test]
+/// The draw order of decks only depends on the seed of the machine.
+fn deck_shuffle() {
+    assert_eq!(shuffled_deck(42), shuffled_deck(42));
+    assert_ne!(shuffled_deck(42), shuffled_deck(7));
+}
+
+#[test]
+/// Decks which don't follow the rules are reported.
+fn deck_validation() {
+    let mut config = deck_config();
+    config.deck_rules.max_copies = 2;
+    match Machine::new(&config).err() {
+        Some(SetupError::TooManyCopies {
+            player: 2,
+            copies: 3,
+            max: 2,
+            ..
+        }) => {}
+        e => panic!("Unexpected result {:?}", e),
+    }
+
+    let mut config = deck_config();
+    config.deck_rules.banned_cards.push(CardId::new(3, 2));
+    match Machine::new(&config).err() {
+        Some(SetupError::BannedCard { player: 1, card }) => {
+            assert_eq!(card, CardId::new(3, 2))
+        }
+        e => panic!("Unexpected result {:?}", e),
+    }
+
+    let mut config = deck_config();
+    config.deck_rules.allowed_sets = Some(vec![0]);
+    match Machine::new(&config).err() {
+        Some(SetupError::DisallowedSet {
+            player: 1, set: 3, ..
+        }) => {}
+        e => panic!("Unexpected result {:?}", e),
+    }
+
+    let mut config = deck_config();
+    config.decks[1] = Some(DeckList::new().with(CardId::new(3, 3), 2));
+    match Machine::new(&config).err() {
+        Some(SetupError::DeckSize {
+            player: 2, size: 2, ..
+        }) => {}
+        e => panic!("Unexpected result {:?}", e),
+    }
+
+    let mut config = deck_config();
+    config.decks[0] = Some(DeckList::new().with(CardId::new(4, 1), 3));
+    match Machine::new(&config).err() {
+        Some(SetupError::UnknownDeckCard { player: 1, card }) => {
+            assert_eq!(card, CardId::new(4, 1))
+        }
+        e => panic!("Unexpected result {:?}", e),
+    }
+}
@@ -0,0 +1,326 @@
+#![feature(use_extern_macros)]
+
+#[macro_use]
+extern crate game_system;
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use game_system::card::PLAYER_CARD_ID;
+use game_system::lifecycle::error::SpawnError;
+use game_system::prelude::prototype::Player;
+use game_system::prelude::*;
+use game_system::re_export::service::TriggerOptions;
+use game_system::runtime::exec_triggers;
+use game_system::zone::play_card;
+
+static END_TURN_COUNT: AtomicUsize = AtomicUsize::new(0);
+static SECOND_PLAYER_COUNT: AtomicUsize = AtomicUsize::new(0);
+static PLAYED_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn counting_card() -> Card {
+    card_impl!{
+        ID = CardId::new(1, 1);
+        NAME = "Counter";
+        properties {
+            EntityTags::Health = 3;
+        }
+
+        triggers {
+            count_end_turn [
+                TIMING = Peri;
+                TRIGGER = EndTurn;
+            ] => |machine| {
+                END_TURN_COUNT.fetch_add(1, Ordering::SeqCst);
+                Ok(machine)
+            }
+        }
+    }
+}
+
+fn conditional_card() -> Card {
+    card_impl!{
+        ID = CardId::new(1, 2);
+        NAME = "Conditional counter";
+
+        triggers {
+            count_second_player [
+                TIMING = Peri;
+                TRIGGER = EndTurn;
+                CONDITION = |machine| {
+                    let game = machine.entities.get(GAME_E_ID).unwrap();
+                    game.get_value_default(&EntityTags::CurrentPlayerOrd) == 2
+                };
+            ] => |machine| {
+                SECOND_PLAYER_COUNT.fetch_add(1, Ordering::SeqCst);
+                Ok(machine)
+            }
+        }
+    }
+}
+
+fn set_current_player(machine: &mut Machine<Wait<Input>, EmptyStack>, player: u32) {
+    let game = machine.entities.get_mut(GAME_E_ID).unwrap();
+    game.set_value(EntityTags::CurrentPlayerOrd, player);
+}
+
+fn start(machine: Machine<Wait<Start>, EmptyStack>) -> Machine<Wait<Input>, EmptyStack> {
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    action.transition(transaction::Epsilon)
+}
+
+fn end_turn(machine: Machine<Wait<Input>, EmptyStack>) -> Machine<Wait<Input>, EmptyStack> {
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let mut effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+    let action: Machine<Action<EndTurn>, _> = effect.pullup().unwrap();
+    action.transition(transaction::Epsilon)
+}
+
+/// Spawns an entity from the provided card and plays it from the hand of the
+/// first player.
+fn spawn_in_play(
+    mut machine: Machine<Wait<Start>, EmptyStack>,
+    card: Card,
+) -> (Machine<Wait<Start>, EmptyStack>, EntityId) {
+    let card_id = card.id();
+    machine.cards.register_card(card).unwrap();
+    let player = machine.entities.first_with_proto::<Player>().unwrap().id();
+    let entity_id = machine.spawn_from_card(card_id, player).unwrap().id();
+    machine
+        .zones
+        .insert(entity_id, player, ZoneKind::Hand, None)
+        .unwrap();
+    // The entity is not in play yet.
+    assert_eq!(
+        machine
+            .triggers
+            .retrieve_triggers_owned_by(entity_id)
+            .count(),
+        0
+    );
+
+    let (_, parts) = machine.into_parts();
+    let machine: Machine<Trigger<Peri, Start>, EmptyStack> =
+        unsafe { Machine::from_parts(transaction::Epsilon, parts) };
+    let machine = play_card(machine, entity_id, None).unwrap();
+    let (_, parts) = machine.into_parts();
+    (unsafe { Machine::from_parts(transaction::Epsilon, parts) }, entity_id)
+}
+
+#[test]
+/// Triggers of a card respond to the game while the spawned entity is in play.
+fn card_triggers_activate() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    let (machine, entity_id) = spawn_in_play(machine, counting_card());
+
+    {
+        let entity = machine.entities.get(entity_id).unwrap();
+        assert_eq!(entity.get_value_default(&EntityTags::Health), 3);
+        let trigger = machine
+            .triggers
+            .retrieve_triggers_owned_by(entity_id)
+            .next()
+            .unwrap();
+        assert_eq!(trigger.name.as_ref().unwrap(), "count_end_turn");
+    }
+    {
+        // The card itself keeps it's dormant triggers.
+        let card = machine.cards.get_card(CardId::new(1, 1)).unwrap();
+        assert_eq!(card.triggers.retrieve_all_triggers().count(), 1);
+    }
+
+    let mut machine = start(machine);
+    machine = end_turn(machine);
+    assert_eq!(END_TURN_COUNT.load(Ordering::SeqCst), 1);
+
+    assert_eq!(machine.deactivate_entity(entity_id), 1);
+    machine = end_turn(machine);
+    assert_eq!(END_TURN_COUNT.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+/// Triggers with a condition only respond when that condition holds.
+fn card_trigger_condition() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    let (machine, _) = spawn_in_play(machine, conditional_card());
+
+    let mut machine = start(machine);
+    set_current_player(&mut machine, 1);
+    machine = end_turn(machine);
+    assert_eq!(SECOND_PLAYER_COUNT.load(Ordering::SeqCst), 0);
+
+    set_current_player(&mut machine, 2);
+    machine = end_turn(machine);
+    assert_eq!(SECOND_PLAYER_COUNT.load(Ordering::SeqCst), 1);
+}
+
+type EndTurnMachine = Machine<Trigger<Peri, EndTurn>, AnyStack>;
+type EndTurnClosure = Arc<Fn(EndTurnMachine) -> Result<EndTurnMachine, MachineError> + Send + Sync>;
+
+/// Builds a trigger which damages the game entity by the provided amount.
+fn damage_game(amount: u32) -> EndTurnClosure {
+    Arc::new(move |mut machine: EndTurnMachine| {
+        {
+            let game = machine.entities.get_mut(GAME_E_ID).unwrap();
+            let damage = game.get_value_default(&EntityTags::Damage);
+            game.set_value(EntityTags::Damage, damage + amount);
+        }
+        Ok(machine)
+    })
+}
+
+#[test]
+/// Closures capture their parameters, so one implementation serves multiple triggers.
+fn closure_triggers() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine
+        .triggers
+        .add_closure_trigger(damage_game(2), TriggerOptions::default());
+    let shared = damage_game(3);
+    for _ in 0..2 {
+        machine
+            .triggers
+            .add_closure_trigger(shared.clone(), TriggerOptions::default());
+    }
+
+    let machine = end_turn(start(machine));
+    let game = machine.entities.get(GAME_E_ID).unwrap();
+    assert_eq!(game.get_value_default(&EntityTags::Damage), 8);
+}
+
+#[test]
+/// Ids of removed entities stay invalid, even when their storage is reused.
+fn removed_entity_ids() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    let amount = machine.entities.len();
+    let (mut machine, old_id) = spawn_in_play(machine, counting_card());
+    assert!(machine.remove_entity(old_id).is_ok());
+    assert_eq!(machine.entities.len(), amount);
+    assert_eq!(
+        machine.triggers.retrieve_triggers_owned_by(old_id).count(),
+        0
+    );
+
+    let new_id = machine
+        .spawn_from_card(CardId::new(1, 1), GAME_E_ID)
+        .unwrap()
+        .id();
+    assert_ne!(old_id, new_id);
+    assert_eq!(machine.entities.len(), amount + 1);
+    assert!(machine.entities.get(old_id).is_err());
+    assert!(machine.entities.get(new_id).is_ok());
+    assert!(machine.remove_entity(old_id).is_err());
+    // Only living entities are visited.
+    assert_eq!(machine.entities.iter().count(), amount + 1);
+}
+
+#[test]
+/// Removing an entity keeps all other entities addressable by their id.
+fn removed_entity_keeps_others() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.cards.register_card(counting_card()).unwrap();
+    let ids: Vec<EntityId> = (0..3)
+        .map(|_| {
+            machine
+                .spawn_from_card(CardId::new(1, 1), GAME_E_ID)
+                .unwrap()
+                .id()
+        })
+        .collect();
+
+    assert!(machine.remove_entity(ids[0]).is_ok());
+    for &id in &ids[1..] {
+        assert_eq!(machine.entities.get(id).unwrap().id(), id);
+    }
+    assert!(machine.remove_entity(ids[2]).is_ok());
+    assert_eq!(machine.entities.get(ids[1]).unwrap().id(), ids[1]);
+}
+
+#[test]
+/// Entities built from registered cards remember their card and owner.
+fn spawn_from_registered_card() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let card_id = CardId::new(1, 1);
+    machine.cards.register_card(counting_card()).unwrap();
+    let player_id = {
+        let player = machine.entities.get(1).unwrap();
+        assert_eq!(player.card_id, Some(PLAYER_CARD_ID));
+        player.id()
+    };
+
+    let entity_id = {
+        let entity = machine.spawn_from_card(card_id, player_id).unwrap();
+        assert_eq!(entity.get_value_default(&EntityTags::Health), 3);
+        assert_eq!(entity.card_id, Some(card_id));
+        assert_eq!(entity.owner, Some(player_id));
+        entity.id()
+    };
+    // The entity is not in play yet.
+    assert_eq!(
+        machine
+            .triggers
+            .retrieve_triggers_owned_by(entity_id)
+            .count(),
+        0
+    );
+    assert_eq!(machine.activate_entity(entity_id).len(), 1);
+
+    let error = machine.spawn_from_card(CardId::new(9, 9), player_id).err();
+    match error {
+        Some(SpawnError::MissingCard(_)) => {}
+        e => panic!("Unexpected result {:?}", e),
+    }
+}
+
+fn played_card() -> Card {
+    card_impl!{
+        ID = CardId::new(1, 3);
+        NAME = "Played counter";
+
+        triggers {
+            count_played [
+                TIMING = Peri;
+                TRIGGER = ZoneChanged;
+            ] => |machine| {
+                PLAYED_COUNT.fetch_add(1, Ordering::SeqCst);
+                Ok(machine)
+            }
+        }
+    }
+}
+
+/// The first player plays the first card of their hand.
+fn play_from_hand<CTS>(
+    x: Machine<Trigger<Peri, EndTurn>, CTS>,
+) -> Result<Machine<Trigger<Peri, EndTurn>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    let player = x.entities.first_with_proto::<Player>().unwrap().id();
+    let card = x.zones.entities(player, ZoneKind::Hand)[0];
+    play_card(x, card, None)
+}
+
+#[test]
+/// Spawned cards respond to the game once they are played, their triggers
+/// are activated only once.
+fn play_spawned_card() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.cards.register_card(played_card()).unwrap();
+    machine.triggers.add_trigger(play_from_hand::<AnyStack>);
+    let player = machine.entities.first_with_proto::<Player>().unwrap().id();
+    let card = machine
+        .spawn_from_card(CardId::new(1, 3), player)
+        .unwrap()
+        .id();
+    machine
+        .zones
+        .insert(card, player, ZoneKind::Hand, None)
+        .unwrap();
+
+    let machine = end_turn(start(machine));
+    assert_eq!(PLAYED_COUNT.load(Ordering::SeqCst), 1);
+    assert_eq!(machine.triggers.retrieve_triggers_owned_by(card).count(), 1);
+}
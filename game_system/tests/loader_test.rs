@@ -0,0 +1,128 @@
+#![cfg(feature = "card_loader")]
+
+extern crate game_system;
+
+use std::fmt::Debug;
+
+use game_system::loader::error::CardLoadErrorKind;
+use game_system::loader::load_cards_str;
+use game_system::prelude::*;
+use game_system::snapshot::TriggerRegistry;
+
+fn print_on_play<CTS>(
+    x: Machine<Trigger<Peri, PlayCard>, CTS>,
+) -> Result<Machine<Trigger<Peri, PlayCard>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    println!("[TRIGGER]\tCard played!");
+    Ok(x)
+}
+
+fn registry() -> TriggerRegistry {
+    let mut registry = TriggerRegistry::new();
+    registry
+        .register("print_on_play", print_on_play::<AnyStack>)
+        .unwrap();
+    registry
+}
+
+const CARDS: &str = r#"
+[[card]]
+set = 1
+id = 1
+name = "Wizard"
+triggers = ["print_on_play"]
+
+[card.properties]
+Attack = 5
+Health = 3
+
+[[card]]
+set = 1
+id = 2
+name = "Wall"
+
+[card.properties]
+Health = 8
+"#;
+
+#[test]
+/// Cards are built from their definition within the file.
+fn load_card_file() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let ids = load_cards_str(CARDS, "cards.toml", &registry(), &mut machine.cards).unwrap();
+    assert_eq!(ids, vec![CardId::new(1, 1), CardId::new(1, 2)]);
+
+    let wizard = machine.cards.get_card(CardId::new(1, 1)).unwrap();
+    assert_eq!(wizard.name, "Wizard");
+    assert_eq!(wizard.get_value_default(&EntityTags::Attack), 5);
+    assert_eq!(wizard.get_value_default(&EntityTags::Health), 3);
+    let trigger = wizard.triggers.retrieve_all_triggers().next().unwrap();
+    assert_eq!(trigger.name.as_ref().unwrap(), "print_on_play");
+}
+
+#[test]
+/// Invalid definitions are reported with their location and nothing is registered.
+fn load_card_errors() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let registry = registry();
+
+    let source = CARDS.replace("Attack = 5", "Strength = 5");
+    let error = load_cards_str(&source, "cards.toml", &registry, &mut machine.cards)
+        .err()
+        .unwrap();
+    assert_eq!((error.file.as_str(), error.line), ("cards.toml", 9));
+    match error.kind {
+        CardLoadErrorKind::UnknownTag(ref e) => assert_eq!(e.0, "Strength"),
+        ref e => panic!("Unexpected error {:?}", e),
+    }
+    assert!(machine.cards.get_card(CardId::new(1, 1)).is_err());
+
+    // Headers can hold whitespace and comments, keys only sharing a prefix with the
+    // unknown tag are skipped.
+    let source = CARDS
+        .replacen("[[card]]", "[[ card ]] # Wizard", 1)
+        .replace("Health = 8", "Health = 8\nHea = 1");
+    let error = load_cards_str(&source, "cards.toml", &registry, &mut machine.cards)
+        .err()
+        .unwrap();
+    assert_eq!(error.line, 19);
+    match error.kind {
+        CardLoadErrorKind::UnknownTag(ref e) => assert_eq!(e.0, "Hea"),
+        ref e => panic!("Unexpected error {:?}", e),
+    }
+
+    let source = CARDS.replace("\"print_on_play\"", "\"print_on_draw\"");
+    let error = load_cards_str(&source, "cards.toml", &registry, &mut machine.cards)
+        .err()
+        .unwrap();
+    assert_eq!(error.line, 6);
+    match error.kind {
+        CardLoadErrorKind::UnknownTrigger(ref e) => assert_eq!(e.0, "print_on_draw"),
+        ref e => panic!("Unexpected error {:?}", e),
+    }
+
+    let source = CARDS.replace("id = 2", "id = 1");
+    let error = load_cards_str(&source, "cards.toml", &registry, &mut machine.cards)
+        .err()
+        .unwrap();
+    assert_eq!(error.line, 12);
+    match error.kind {
+        CardLoadErrorKind::DuplicateCard(ref e) => assert_eq!(e.0, CardId::new(1, 1)),
+        ref e => panic!("Unexpected error {:?}", e),
+    }
+
+    let error = load_cards_str(
+        "[[card]\nset = 1",
+        "cards.toml",
+        &registry,
+        &mut machine.cards,
+    )
+    .err()
+    .unwrap();
+    match error.kind {
+        CardLoadErrorKind::Syntax(_) => assert_eq!(error.line, 1),
+        ref e => panic!("Unexpected error {:?}", e),
+    }
+}
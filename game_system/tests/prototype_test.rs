@@ -0,0 +1,108 @@
+extern crate game_system;
+extern crate medici_core;
+
+use medici_core::value_from_type_traits::IntoEnum;
+
+use game_system::prelude::error::custom_type::EntityAccessError;
+use game_system::prelude::prototype::{Game, Player, ProtoItem};
+use game_system::prelude::*;
+
+#[test]
+/// Prototypes are attached to entities through their enumeration variant.
+fn entity_prototypes() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    let game_entity = machine.entities.get(GAME_E_ID).unwrap();
+
+    assert!(game_entity.prototypes.contains(&ProtoItem::GameProto));
+    assert!(!game_entity.prototypes.contains(&ProtoItem::PlayerProto));
+    assert!(game_entity.as_proto::<Game>().is_ok());
+    assert!(game_entity.as_proto::<Player>().is_err());
+}
+
+#[test]
+/// Generated prototypes wrap an entity and map onto their enumeration variant.
+fn generated_prototypes() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    let game_entity = machine.entities.get(GAME_E_ID).unwrap();
+
+    let game: Game = game_entity.into();
+    assert_eq!(game.0.unwrap().id(), GAME_E_ID);
+    assert_eq!(<Game as IntoEnum<ProtoItem>>::into_enum(), ProtoItem::GameProto);
+    assert_eq!(<Player as IntoEnum<ProtoItem>>::into_enum(), ProtoItem::PlayerProto);
+}
+
+#[test]
+/// Entities can be looked up by their prototypes and properties.
+fn entity_queries() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    assert_eq!(machine.entities.with_proto::<Player>().count(), 2);
+    let game_id = machine.entities.first_with_proto::<Game>().unwrap().id();
+    assert_eq!(game_id, GAME_E_ID);
+
+    for mut player in machine.entities.with_proto_mut::<Player>() {
+        let entity = player.0.unwrap_mut().unwrap();
+        entity.set_value(EntityTags::Health, 30);
+    }
+    {
+        let mut game = machine.entities.first_with_proto_mut::<Game>().unwrap();
+        let entity = game.0.unwrap_mut().unwrap();
+        entity.set_value(EntityTags::Health, 0);
+    }
+
+    let alive: Vec<EntityId> = machine
+        .entities
+        .where_tag(EntityTags::Health, |v| v > 0)
+        .map(|e| e.id())
+        .collect();
+    let players: Vec<EntityId> = machine
+        .entities
+        .with_proto::<Player>()
+        .map(|e| e.id())
+        .collect();
+    assert_eq!(alive, players);
+    assert_eq!(machine.entities.ids().count(), 3);
+}
+
+#[test]
+/// Multiple entities can be mutated at the same time.
+fn disjoint_entity_access() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let players: Vec<EntityId> = machine
+        .entities
+        .with_proto::<Player>()
+        .map(|e| e.id())
+        .collect();
+
+    {
+        let (attacker, defender) = machine
+            .entities
+            .get_pair_mut(players[0], players[1])
+            .unwrap();
+        attacker.set_value(EntityTags::Attack, 2);
+        let damage = attacker.get_value_default(&EntityTags::Attack);
+        defender.set_value(EntityTags::Damage, damage);
+    }
+    {
+        let defender = machine.entities.get(players[1]).unwrap();
+        assert_eq!(defender.get_value_default(&EntityTags::Damage), 2);
+    }
+
+    let duplicate = machine.entities.get_pair_mut(players[0], players[0]).err();
+    assert_eq!(duplicate, Some(EntityAccessError::Duplicate(players[0])));
+    let missing = machine.entities.get_many_mut(&[players[0], 99]).err();
+    assert_eq!(missing, Some(EntityAccessError::Missing(99)));
+
+    let prototypes = machine
+        .entities
+        .get_pair_proto_mut::<Game, Player>(GAME_E_ID, players[0])
+        .is_ok();
+    assert!(prototypes);
+    let wrong_proto = machine
+        .entities
+        .get_many_proto_mut::<Player>(&[GAME_E_ID, players[0]])
+        .err();
+    assert_eq!(
+        wrong_proto,
+        Some(EntityAccessError::MissingPrototype(GAME_E_ID))
+    );
+}
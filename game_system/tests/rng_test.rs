@@ -0,0 +1,59 @@
+extern crate game_system;
+
+use game_system::prelude::*;
+use game_system::re_export::service::RngService;
+
+fn seeded(seed: u64) -> Machine<Wait<Start>, EmptyStack> {
+    let mut config: SetupConfig = Default::default();
+    config.seed = seed;
+    Machine::new(&config).unwrap()
+}
+
+/// Rolls a die through the service of any machine.
+fn roll<M: ServiceCompliance<RngService>>(machine: &mut M) -> usize {
+    machine.get_mut().range(1, 7)
+}
+
+#[test]
+/// Machines with the same seed generate the same numbers.
+fn deterministic_rng() {
+    let mut first = seeded(42);
+    let mut second = seeded(42);
+    let first_rolls: Vec<usize> = (0..20).map(|_| roll(&mut first)).collect();
+    let second_rolls: Vec<usize> = (0..20).map(|_| roll(&mut second)).collect();
+    assert_eq!(first_rolls, second_rolls);
+    assert!(first_rolls.iter().all(|&r| r >= 1 && r < 7));
+
+    let mut other = seeded(7);
+    let other_rolls: Vec<usize> = (0..20).map(|_| roll(&mut other)).collect();
+    assert_ne!(first_rolls, other_rolls);
+
+    // Clones continue the sequence of the original machine.
+    let mut clone = first.clone();
+    assert_eq!(clone.rng.next_u64(), first.rng.next_u64());
+}
+
+#[test]
+/// Shuffling and choosing are deterministic for the same seed.
+fn shuffle_and_choose() {
+    let mut first = seeded(42);
+    let mut second = seeded(42);
+
+    let mut first_items: Vec<u32> = (0..10).collect();
+    let mut second_items = first_items.clone();
+    first.rng.shuffle(&mut first_items);
+    second.rng.shuffle(&mut second_items);
+    assert_eq!(first_items, second_items);
+
+    let mut sorted = first_items.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..10).collect::<Vec<u32>>());
+
+    assert_eq!(
+        first.rng.choose(&first_items),
+        second.rng.choose(&second_items)
+    );
+    let empty: [u32; 0] = [];
+    assert_eq!(first.rng.choose(&empty), None);
+    assert_eq!(first.rng.seed(), 42);
+}
@@ -0,0 +1,172 @@
+#![feature(use_extern_macros)]
+
+extern crate game_system;
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use game_system::prelude::error::custom_type::TriggerFail;
+use game_system::prelude::*;
+use game_system::re_export::ct;
+use game_system::re_export::service::TriggerOptions;
+use game_system::runtime::{exec_recurse, exec_triggers};
+
+static DAMAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+static PREVENTED_DAMAGE: AtomicBool = AtomicBool::new(false);
+static RECOVERED_DAMAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Ending the turn damages, which causes a new chain of triggers.
+fn damage_on_end_turn<CTS>(
+    x: Machine<Trigger<Peri, EndTurn>, CTS>,
+) -> Result<Machine<Trigger<Peri, EndTurn>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    exec_recurse::<Damage, _, _, _, _, _, _>(x, transaction::Epsilon)
+}
+
+fn count_damage<CTS>(
+    x: Machine<Trigger<Peri, Damage>, CTS>,
+) -> Result<Machine<Trigger<Peri, Damage>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    DAMAGE_COUNT.fetch_add(1, Ordering::SeqCst);
+    Ok(x)
+}
+
+#[test]
+/// Triggers can cause new trigger chains, the machine returns into the state
+/// it recursed from.
+fn recursive_triggers() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(damage_on_end_turn::<AnyStack>);
+    machine.triggers.add_trigger(count_damage::<AnyStack>);
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let mut effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+    let action: Machine<Action<EndTurn>, _> = effect.pullup().unwrap();
+    let mut machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+
+    assert_eq!(DAMAGE_COUNT.load(Ordering::SeqCst), 1);
+    assert!(machine.history_names().is_empty());
+    // All transactions stored by the recursion are consumed again.
+    assert!(machine.transactions.pop().is_err());
+}
+
+/// Damaging causes new damage, which never ends.
+fn damage_on_damage<CTS>(
+    x: Machine<Trigger<Peri, Damage>, CTS>,
+) -> Result<Machine<Trigger<Peri, Damage>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    exec_recurse::<Damage, _, _, _, _, _, _>(x, transaction::Epsilon)
+}
+
+fn named(name: &'static str) -> TriggerOptions {
+    TriggerOptions {
+        name: Some(name.into()),
+        ..Default::default()
+    }
+}
+
+#[test]
+/// Triggers which keep causing each other are stopped at the configured depth.
+fn recursion_limit() {
+    let config = SetupConfig {
+        max_trigger_recursion: 4,
+        ..Default::default()
+    };
+    let mut machine = Machine::new(&config).unwrap();
+    machine
+        .triggers
+        .add_trigger_with(damage_on_end_turn::<AnyStack>, named("end_turn"));
+    machine
+        .triggers
+        .add_trigger_with(damage_on_damage::<AnyStack>, named("damage_loop"));
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    let error = exec_triggers(effect, transaction::Epsilon).err().unwrap();
+
+    // The outer trigger started the first recursion, each recursion after
+    // that was started by the looping trigger.
+    let mut chain = vec!["end_turn".to_string()];
+    chain.extend(vec!["damage_loop".to_string(); 4]);
+    assert_eq!(error.kind(), &ErrorKind::RecursionLimit(chain));
+}
+
+/// The first damage fails, all damage after that is counted.
+fn fail_damage_once<CTS>(
+    x: Machine<Trigger<Peri, Damage>, CTS>,
+) -> Result<Machine<Trigger<Peri, Damage>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    if !PREVENTED_DAMAGE.swap(true, Ordering::SeqCst) {
+        return Err(TriggerFail::ConstraintFail.infuse_with("Damage is prevented once", x));
+    }
+    RECOVERED_DAMAGE_COUNT.fetch_add(1, Ordering::SeqCst);
+    Ok(x)
+}
+
+type EffectHistory = ct!(Effect<EndTurn> => EmptyStack, from Action<EndTurn>);
+type RecurseHistory =
+    ct!(RecurseEffect<Damage> => EffectHistory, from Trigger<Peri, EndTurn>);
+type PreHistory = ct!(Trigger<Pre, Damage> => RecurseHistory, from RecurseEffect<Damage>);
+type PeriHistory = ct!(Trigger<Peri, Damage> => PreHistory, from Trigger<Pre, Damage>);
+
+#[test]
+/// Machines retrieved from an error no longer register the aborted triggers and
+/// can execute triggers again.
+fn recover_after_error() {
+    let config = SetupConfig {
+        max_trigger_recursion: 1,
+        ..Default::default()
+    };
+    let mut machine = Machine::new(&config).unwrap();
+    machine
+        .triggers
+        .add_trigger_with(damage_on_end_turn::<AnyStack>, named("end_turn"));
+    machine
+        .triggers
+        .add_trigger_with(fail_damage_once::<AnyStack>, named("prevent_damage"));
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    let error = exec_triggers(effect, transaction::Epsilon).err().unwrap();
+    assert_eq!(error.state_name(), "Trigger<Peri, Damage>");
+
+    // The machine is only returned as the type it was stored with.
+    let error = error
+        .into_machine::<Machine<Trigger<Peri, Damage>, EmptyStack>>()
+        .err()
+        .unwrap();
+    let machine = error
+        .into_machine::<Machine<Trigger<Peri, Damage>, PeriHistory>>()
+        .unwrap();
+    assert!(machine.triggers.active_chain().is_empty());
+    assert_eq!(machine.triggers.recursion_depth(), 0);
+
+    let machine: Machine<Trigger<Pre, Damage>, PreHistory> = machine.pullup().unwrap();
+    let machine: Machine<RecurseEffect<Damage>, RecurseHistory> = machine.pullup().unwrap();
+    let machine: Machine<Trigger<Peri, EndTurn>, EffectHistory> = machine.pullup().unwrap();
+    let machine: Machine<Trigger<Post, EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<EndTurn>, _> = machine.transition(transaction::Epsilon);
+
+    let effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+    assert_eq!(RECOVERED_DAMAGE_COUNT.load(Ordering::SeqCst), 1);
+    assert!(effect.triggers.active_chain().is_empty());
+    assert_eq!(effect.triggers.recursion_depth(), 0);
+}
@@ -0,0 +1,123 @@
+extern crate game_system;
+#[cfg(feature = "serde-support")]
+extern crate serde_json;
+
+use std::fmt::Debug;
+
+use game_system::prelude::*;
+use game_system::re_export::service::CardService;
+use game_system::snapshot::error::SnapshotError;
+use game_system::snapshot::TriggerRegistry;
+
+fn print_on_start<CTS>(
+    x: Machine<Trigger<Peri, Start>, CTS>,
+) -> Result<Machine<Trigger<Peri, Start>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    println!("[TRIGGER]\tGame Started!");
+    Ok(x)
+}
+
+fn registry() -> TriggerRegistry {
+    let mut registry = TriggerRegistry::new();
+    registry
+        .register("print_on_start", print_on_start::<AnyStack>)
+        .unwrap();
+    registry
+}
+
+fn build_machine() -> Machine<Wait<Start>, EmptyStack> {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(print_on_start::<AnyStack>);
+    machine
+        .entities
+        .get_mut(GAME_E_ID)
+        .unwrap()
+        .set_value(EntityTags::StartHandSize, 4);
+    machine
+}
+
+#[test]
+fn snapshot_roundtrip() {
+    let registry = registry();
+    let machine = build_machine();
+
+    let snapshot = machine.snapshot(&registry).unwrap();
+    assert_eq!(snapshot.state, "Wait<Start>");
+    assert_eq!(snapshot.triggers.triggers[0].callback, "print_on_start");
+
+    let restored: Machine<Wait<Start>, EmptyStack> =
+        Machine::restore(snapshot, machine.cards.clone(), &registry).unwrap();
+    let game_entity = restored.entities.get(GAME_E_ID).unwrap();
+    assert_eq!(game_entity.get_value(&EntityTags::StartHandSize).unwrap(), 4);
+    assert_eq!(restored.triggers.retrieve_all_triggers().count(), 1);
+    // Cards of the persisted machine are kept.
+    let card_id = game_entity.card_id.unwrap();
+    assert!(restored.cards.get_card(card_id).is_ok());
+}
+
+#[test]
+/// The recursion limit of the trigger service survives a snapshot.
+fn snapshot_recursion_limit() {
+    let registry = registry();
+    let mut machine = build_machine();
+    machine.triggers.set_recursion_limit(3);
+
+    let snapshot = machine.snapshot(&registry).unwrap();
+    assert_eq!(snapshot.triggers.recursion_limit, 3);
+    let restored: Machine<Wait<Start>, EmptyStack> =
+        Machine::restore(snapshot, machine.cards.clone(), &registry).unwrap();
+    assert_eq!(restored.triggers.recursion_limit(), 3);
+}
+
+#[test]
+/// Entities can only be restored when the cards they were spawned from are known.
+fn restore_missing_cards() {
+    let registry = registry();
+    let snapshot = build_machine().snapshot(&registry).unwrap();
+    let restored: Result<Machine<Wait<Start>, EmptyStack>, _> =
+        Machine::restore(snapshot, CardService::new(), &registry);
+    match restored {
+        Err(SnapshotError::MissingCard(_)) => {}
+        x => panic!("Unexpected result {:?}", x),
+    }
+}
+
+#[test]
+fn snapshot_unregistered_trigger() {
+    let machine = build_machine();
+    match machine.snapshot(&TriggerRegistry::new()) {
+        Err(SnapshotError::UnregisteredTrigger(_)) => {}
+        x => panic!("Unexpected result {:?}", x),
+    }
+}
+
+#[test]
+fn restore_other_state() {
+    let registry = registry();
+    let snapshot = build_machine().snapshot(&registry).unwrap();
+    let restored: Result<Machine<Wait<Input>, EmptyStack>, _> =
+        Machine::restore(snapshot, CardService::new(), &registry);
+    match restored {
+        Err(SnapshotError::StateMismatch { .. }) => {}
+        x => panic!("Unexpected result {:?}", x),
+    }
+}
+
+#[cfg(feature = "serde-support")]
+#[test]
+fn snapshot_serde() {
+    let registry = registry();
+    let mut machine = build_machine();
+    machine.triggers.set_recursion_limit(3);
+    let snapshot = machine.snapshot(&registry).unwrap();
+
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot = serde_json::from_str(&json).unwrap();
+    let restored: Machine<Wait<Start>, EmptyStack> =
+        Machine::restore(snapshot, machine.cards.clone(), &registry).unwrap();
+    let game_entity = restored.entities.get(GAME_E_ID).unwrap();
+    assert_eq!(game_entity.get_value(&EntityTags::StartHandSize).unwrap(), 4);
+    assert_eq!(restored.triggers.recursion_limit(), 3);
+}
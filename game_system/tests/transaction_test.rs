@@ -0,0 +1,42 @@
+#![feature(try_from, use_extern_macros)]
+
+extern crate game_system;
+extern crate medici_core;
+extern crate medici_derive;
+
+use std::convert::TryFrom;
+
+use medici_core::marker::Transaction;
+use medici_derive::TransactionContainer;
+
+use game_system::prelude::transaction::{Epsilon, TransactionItem};
+
+#[derive(Debug, Clone, Copy)]
+struct Damage(u32);
+impl Transaction for Damage {}
+
+#[derive(Debug, Clone, TransactionContainer)]
+enum TestItem {
+    Epsilon(Epsilon),
+    Damage(Damage),
+}
+
+#[test]
+fn unpack_transaction() {
+    let item: TransactionItem = Epsilon.into();
+    assert_eq!(item.variant_name(), "Epsilon");
+    assert!(Epsilon::try_from(item).is_ok());
+
+    let item: TestItem = Damage(5).into();
+    let damage = Damage::try_from(item).expect("Unpacking failed");
+    assert_eq!(damage.0, 5);
+}
+
+#[test]
+/// The unpack error names the expected and contained variant.
+fn unpack_mismatch() {
+    let item: TestItem = Damage(5).into();
+    let error = Epsilon::try_from(item).unwrap_err();
+    assert_eq!(error.expected, "Epsilon");
+    assert_eq!(error.factual, "Damage");
+}
@@ -0,0 +1,190 @@
+#![feature(use_extern_macros)]
+
+extern crate game_system;
+extern crate medici_core;
+extern crate medici_derive;
+
+use std::marker::PhantomData;
+
+use medici_derive::State as StateDerive;
+
+use game_system::prelude::*;
+use game_system::re_export::function::{State, StateContainer};
+use game_system::re_export::{ct, StateGraph, TransitionEdge, TransitionKind};
+use game_system::state_machine::transitions::{state_graph, TRANSITIONS};
+
+fn has_edge(kind: TransitionKind, from: &str, into: &str) -> bool {
+    TRANSITIONS
+        .iter()
+        .any(|e: &TransitionEdge| e.kind == kind && e.from == from && e.into == into)
+}
+
+#[test]
+/// The action shorthand declares the complete effect chain of the action.
+fn action_edges() {
+    assert!(has_edge(TransitionKind::Transition, "Wait<Input>", "Action<EndTurn>"));
+    assert!(has_edge(TransitionKind::Transition, "Action<EndTurn>", "Wait<Input>"));
+    assert!(has_edge(TransitionKind::Pushdown, "Action<EndTurn>", "Effect<EndTurn>"));
+    assert!(has_edge(TransitionKind::Pullup, "Effect<EndTurn>", "Action<EndTurn>"));
+    assert!(has_edge(
+        TransitionKind::Transition,
+        "Trigger<Post, EndTurn>",
+        "Effect<EndTurn>"
+    ));
+}
+
+#[test]
+/// Each pushdown edge has a matching pullup edge.
+fn pushdown_pairs() {
+    for edge in TRANSITIONS
+        .iter()
+        .filter(|e| e.kind == TransitionKind::Pushdown)
+    {
+        assert!(has_edge(TransitionKind::Pullup, edge.into, edge.from));
+    }
+}
+
+#[test]
+/// Each dead entity is processed within its own nested level, which is left from Post.
+fn death_edges() {
+    let graph = state_graph();
+    let outgoing: Vec<_> = graph.outgoing("DeathEffect<Peri, Death>").collect();
+    assert_eq!(
+        outgoing,
+        vec![(TransitionKind::Transition, "DeathEffect<Post, Death>")]
+    );
+    let outgoing: Vec<_> = graph.outgoing("DeathEffect<Post, Death>").collect();
+    assert_eq!(outgoing, vec![(TransitionKind::Pullup, "Effect<A>")]);
+    assert_eq!(graph.incoming("DeathEffect<Pre, Death>").count(), 1);
+    // Post is only reached through the timings of the nested level.
+    let incoming: Vec<_> = graph.incoming("DeathEffect<Post, Death>").collect();
+    assert_eq!(
+        incoming,
+        vec![(TransitionKind::Transition, "DeathEffect<Peri, Death>")]
+    );
+}
+
+#[test]
+/// Generic edges are templates, which are no states of the machine themselves.
+fn graph_templates() {
+    let graph = state_graph();
+    let templates = graph.templates();
+    assert!(templates.contains(&"Trigger<Pre, TR>"));
+    assert!(templates.contains(&"RecurseEffect<X>"));
+    assert!(templates.contains(&"Effect<A>"));
+    assert!(!graph.is_template("DeathEffect<Pre, Death>"));
+    assert!(!graph.is_template("Trigger<Pre, EndTurn>"));
+    assert!(graph.reachable_from("Trigger<Pre, TR>").is_empty());
+}
+
+#[test]
+/// The machine can only be entered through its initial state and never gets stuck.
+fn graph_reachability() {
+    let graph = state_graph();
+    assert_eq!(graph.states_without_incoming(), vec!["Wait<Start>"]);
+    assert!(graph.states_without_outgoing().is_empty());
+
+    let mut reachable = graph.reachable_from("Wait<Start>");
+    reachable.sort();
+    let mut expected = vec![
+        "Action<Start>",
+        "Effect<Start>",
+        "Trigger<Pre, Start>",
+        "Trigger<Peri, Start>",
+        "Trigger<Post, Start>",
+        "Wait<Input>",
+        "Action<EndTurn>",
+        "Effect<EndTurn>",
+        "Trigger<Pre, EndTurn>",
+        "Trigger<Peri, EndTurn>",
+        "Trigger<Post, EndTurn>",
+        "DeathEffect<Pre, Death>",
+        "DeathEffect<Peri, Death>",
+        "DeathEffect<Post, Death>",
+    ];
+    expected.sort();
+    assert_eq!(reachable, expected);
+}
+
+#[test]
+/// Template edges are resolved against each concrete state they match.
+fn graph_template_resolution() {
+    let graph = state_graph();
+    // The death phase is entered from, and left into, the effect of any action.
+    let death = graph.reachable_from("DeathEffect<Post, Death>");
+    assert!(death.contains(&"Effect<Start>"));
+    assert!(death.contains(&"Effect<EndTurn>"));
+
+    // Arguments shared by both states of an edge are bound to the same value.
+    let edges = [
+        edge(TransitionKind::Transition, "Wait<Start>", "Action<Start>", &[]),
+        edge(TransitionKind::Transition, "Wait<Start>", "Effect<EndTurn>", &[]),
+        edge(TransitionKind::Pushdown, "Action<A>", "Effect<A>", &["A"]),
+    ];
+    let graph = StateGraph::from_edges(edges.iter());
+    assert_eq!(
+        graph.states_without_outgoing(),
+        vec!["Action<Start>", "Effect<EndTurn>"]
+    );
+}
+
+fn edge(
+    kind: TransitionKind,
+    from: &'static str,
+    into: &'static str,
+    generics: &'static [&'static str],
+) -> TransitionEdge {
+    TransitionEdge {
+        kind,
+        from,
+        into,
+        generics,
+    }
+}
+
+#[test]
+fn graph_export() {
+    let graph = state_graph();
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("\"Action<EndTurn>\" -> \"Effect<EndTurn>\" [label=\"pushdown\""));
+
+    let mermaid = graph.to_mermaid();
+    assert!(mermaid.starts_with("graph TD"));
+    assert!(mermaid.contains("[\"Trigger&lt;Peri, EndTurn&gt;\"]"));
+}
+
+#[test]
+/// State names are composed from the names of their arguments.
+fn state_names() {
+    let machine = Machine::new(&Default::default()).unwrap();
+    assert_eq!(machine.state_name(), "Wait<Start>");
+    assert!(machine.history_names().is_empty());
+
+    assert_eq!(<Trigger<Peri, EndTurn> as State>::name(), "Trigger<Peri, EndTurn>");
+    assert_eq!(
+        <ct!(Effect<Start> => ct!(Action<Start> => EmptyStack)) as CTStack>::state_names(),
+        vec!["Action<Start>", "Effect<Start>"]
+    );
+    // Names correspond with the names used within the state graph.
+    let name = <Effect<EndTurn> as State>::name();
+    assert!(state_graph().states().contains(&name.as_str()));
+
+    // Derived states are named after their type, composed from their arguments.
+    assert_eq!(<Custom as State>::name(), "Custom");
+    assert_eq!(<Wait<Custom> as State>::name(), "Wait<Custom>");
+    assert_eq!(<Wrapped<Custom> as State>::name(), "Wrapped<Custom>");
+}
+
+/// State which derives its name.
+#[allow(dead_code)]
+#[derive(Debug, Clone, StateDerive)]
+#[state(transaction = "transaction::Epsilon")]
+struct Custom();
+impl game_system::re_export::marker::Waitable for Custom {}
+
+/// Generic state which derives its name from its argument.
+#[allow(dead_code)]
+#[derive(Debug, Clone, StateDerive)]
+#[state(transaction = "transaction::Epsilon")]
+struct Wrapped<W: State>(PhantomData<W>);
@@ -0,0 +1,289 @@
+extern crate failure;
+extern crate game_system;
+
+use std::any::TypeId;
+use std::cmp::Ordering;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use failure::Fail;
+
+use game_system::card::PLAYER_CARD_ID;
+use game_system::prelude::error::custom_type::TriggerFail;
+use game_system::prelude::*;
+use game_system::re_export::service::trigger::TriggerWrapper;
+use game_system::re_export::service::{current_player_first, TriggerOptions, TriggerOrderContext};
+use game_system::re_export::storage::trigger::UnsafeTrigger;
+use game_system::re_export::storage::TriggerHandle;
+use game_system::runtime::exec_triggers;
+
+fn print_on_start<CTS>(
+    x: Machine<Trigger<Peri, Start>, CTS>,
+) -> Result<Machine<Trigger<Peri, Start>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    println!("[TRIGGER]\tGame Started!");
+    Ok(x)
+}
+
+#[test]
+/// Triggers receive unique handles and keep their metadata.
+fn trigger_metadata() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let anonymous = machine.triggers.add_trigger(print_on_start::<AnyStack>);
+    let named = machine.triggers.add_trigger_with(
+        print_on_start::<AnyStack>,
+        TriggerOptions {
+            name: Some("print_on_start".into()),
+            owner: Some(1),
+            ..Default::default()
+        },
+    );
+    assert!(anonymous != named);
+
+    let trigger = machine
+        .triggers
+        .retrieve_all_triggers()
+        .find(|t| t.handle == named)
+        .unwrap();
+    assert_eq!(trigger.name.as_ref().unwrap(), "print_on_start");
+    assert_eq!(trigger.owner, Some(1));
+    assert_eq!(machine.triggers.retrieve_triggers_owned_by(1).count(), 1);
+}
+
+#[test]
+fn trigger_removal() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let handle = machine.triggers.add_trigger(print_on_start::<AnyStack>);
+    for _ in 0..2 {
+        machine.triggers.add_trigger_with(
+            print_on_start::<AnyStack>,
+            TriggerOptions {
+                owner: Some(2),
+                ..Default::default()
+            },
+        );
+    }
+    assert_eq!(machine.triggers.retrieve_all_triggers().count(), 3);
+
+    assert!(machine.triggers.remove_trigger(handle).is_some());
+    assert!(machine.triggers.remove_trigger(handle).is_none());
+    assert_eq!(machine.triggers.remove_triggers_owned_by(2), 2);
+    assert_eq!(machine.triggers.retrieve_all_triggers().count(), 0);
+}
+
+fn add_ordered(
+    machine: &mut Machine<Wait<Start>, EmptyStack>,
+    owner: EntityId,
+    priority: i32,
+    order: u64,
+) -> TriggerHandle {
+    machine.triggers.add_trigger_with(
+        print_on_start::<AnyStack>,
+        TriggerOptions {
+            owner: Some(owner),
+            priority,
+            order,
+            ..Default::default()
+        },
+    )
+}
+
+fn execution_order(machine: Machine<Wait<Start>, EmptyStack>) -> Vec<TriggerHandle> {
+    let (_, parts) = machine.into_parts();
+    let machine: Machine<Trigger<Peri, Start>, EmptyStack> =
+        unsafe { Machine::from_parts(transaction::Epsilon, parts) };
+    let order = machine
+        .triggers
+        .retrieve_triggers(&machine)
+        .map(|t| t.handle)
+        .collect();
+    order
+}
+
+#[test]
+/// Triggers are executed by descending priority, then by ordering key and
+/// finally in order of registration.
+fn trigger_default_order() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let late = add_ordered(&mut machine, 1, 0, 2);
+    let early = add_ordered(&mut machine, 2, 0, 1);
+    let urgent = add_ordered(&mut machine, 1, 10, 5);
+    let tied = add_ordered(&mut machine, 2, 0, 2);
+
+    assert_eq!(execution_order(machine), vec![urgent, early, late, tied]);
+}
+
+fn by_owner_descending(
+    _: &TriggerOrderContext,
+    a: &UnsafeTrigger<TimingItem, TriggerItem>,
+    b: &UnsafeTrigger<TimingItem, TriggerItem>,
+) -> Ordering {
+    b.owner.cmp(&a.owner).then(a.handle.cmp(&b.handle))
+}
+
+#[test]
+fn trigger_custom_order() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.set_comparator(by_owner_descending);
+    let first = add_ordered(&mut machine, 1, 10, 0);
+    let second = add_ordered(&mut machine, 2, 0, 0);
+
+    assert_eq!(execution_order(machine), vec![second, first]);
+}
+
+#[test]
+/// Triggers controlled by the player on turn are executed before all others.
+fn trigger_current_player_first() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.set_comparator(current_player_first);
+    let players = machine.players().to_vec();
+    let minion = machine
+        .spawn_from_card(PLAYER_CARD_ID, players[1])
+        .unwrap()
+        .id();
+    {
+        let game = machine.entities.get_mut(GAME_E_ID).unwrap();
+        game.set_value(EntityTags::CurrentPlayerOrd, 2);
+    }
+    let game = add_ordered(&mut machine, GAME_E_ID, 10, 0);
+    let opponent = add_ordered(&mut machine, players[0], 5, 0);
+    let controlled = add_ordered(&mut machine, minion, 0, 0);
+    let own = add_ordered(&mut machine, players[1], 0, 1);
+
+    assert_eq!(
+        execution_order(machine),
+        vec![controlled, own, game, opponent]
+    );
+}
+
+#[test]
+/// Triggers refuse to be unpacked for another state machine.
+fn trigger_machine_mismatch() {
+    type M = Machine<Trigger<Peri, Start>, EmptyStack>;
+
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(print_on_start::<AnyStack>);
+    let mut trigger = machine
+        .triggers
+        .retrieve_all_triggers()
+        .next()
+        .cloned()
+        .unwrap();
+
+    let wrapper = unsafe { TriggerWrapper::<M, _, _>::try_from_trigger_entry(trigger.clone()) };
+    assert!(wrapper.is_ok());
+
+    // Pretend the trigger was built for another machine.
+    trigger.machine_family = TypeId::of::<()>();
+    let wrapper = unsafe { TriggerWrapper::<M, _, _>::try_from_trigger_entry(trigger) };
+    assert_eq!(wrapper.err(), Some(TriggerFail::MachineMismatch));
+}
+
+fn print_on_end_turn<CTS>(
+    x: Machine<Trigger<Peri, EndTurn>, CTS>,
+) -> Result<Machine<Trigger<Peri, EndTurn>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    println!("[TRIGGER]\tTurn ended!");
+    Ok(x)
+}
+
+#[test]
+/// Closures refuse to be unpacked for another machine type than they were built for.
+fn trigger_closure_mismatch() {
+    type StartMachine = Machine<Trigger<Peri, Start>, EmptyStack>;
+    type EndTurnMachine = Machine<Trigger<Peri, EndTurn>, EmptyStack>;
+
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let closure: Arc<Fn(StartMachine) -> Result<StartMachine, MachineError> + Send + Sync> =
+        Arc::new(|x: StartMachine| -> Result<StartMachine, MachineError> { Ok(x) });
+    machine
+        .triggers
+        .add_closure_trigger(closure, TriggerOptions::default());
+    machine.triggers.add_trigger(print_on_end_turn::<AnyStack>);
+    let triggers: Vec<UnsafeTrigger<_, _>> =
+        machine.triggers.retrieve_all_triggers().cloned().collect();
+    let mut closure = triggers[0].clone();
+
+    let wrapper =
+        unsafe { TriggerWrapper::<StartMachine, _, _>::try_from_trigger_entry(closure.clone()) };
+    assert!(wrapper.is_ok());
+
+    // Pretend the closure was built for another trigger of the same machine.
+    closure.trigger = triggers[1].trigger;
+    let wrapper =
+        unsafe { TriggerWrapper::<EndTurnMachine, _, _>::try_from_trigger_entry(closure) };
+    assert_eq!(wrapper.err(), Some(TriggerFail::CallbackMismatch));
+}
+
+/// Machine sharing the states of [`Machine`] without being part of it's family.
+#[derive(Debug)]
+struct ForeignMachine;
+
+impl function::StateContainer for ForeignMachine {
+    type Family = ForeignMachine;
+    type State = Trigger<Peri, Start>;
+    type TimingEnum = TimingItem;
+    type TriggerEnum = TriggerItem;
+    type TransitionRecord = EmptyStack;
+    type Normalized = ForeignMachine;
+
+    fn into_normalized(self) -> Self::Normalized {
+        self
+    }
+
+    fn from_normalized(x: Self::Normalized) -> Self {
+        x
+    }
+}
+
+fn foreign_on_start(x: ForeignMachine) -> Result<ForeignMachine, MachineError> {
+    Ok(x)
+}
+
+#[test]
+/// Triggers built for another state machine are reported when executing them.
+fn trigger_foreign_machine() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine
+        .triggers
+        .add_trigger::<ForeignMachine>(foreign_on_start);
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let effect: Machine<Effect<Start>, _> = action.pushdown(transaction::Epsilon);
+    let error = exec_triggers(effect, transaction::Epsilon).err().unwrap();
+
+    assert_eq!(error.kind(), &ErrorKind::ConstraintError);
+    assert_eq!(error.state_name(), "Trigger<Peri, Start>");
+    let cause = error
+        .cause()
+        .and_then(|c| c.downcast_ref::<TriggerFail>())
+        .cloned();
+    assert_eq!(cause, Some(TriggerFail::MachineMismatch));
+}
+
+#[test]
+/// The player on turn is found by id, even after entities were removed.
+fn trigger_current_player_after_removal() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.set_comparator(current_player_first);
+    let players = machine.players().to_vec();
+    let minion = machine
+        .spawn_from_card(PLAYER_CARD_ID, players[1])
+        .unwrap()
+        .id();
+    // The last stored entity takes the storage position of the removed player.
+    assert!(machine.remove_entity(players[0]).is_ok());
+    {
+        let game = machine.entities.get_mut(GAME_E_ID).unwrap();
+        game.set_value(EntityTags::CurrentPlayerOrd, 2);
+    }
+    let game = add_ordered(&mut machine, GAME_E_ID, 10, 0);
+    let controlled = add_ordered(&mut machine, minion, 0, 0);
+    let own = add_ordered(&mut machine, players[1], 0, 1);
+
+    assert_eq!(execution_order(machine), vec![controlled, own, game]);
+}
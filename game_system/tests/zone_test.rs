@@ -0,0 +1,185 @@
+extern crate failure;
+extern crate game_system;
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use failure::Fail;
+
+use game_system::prelude::error::custom_type::ZoneError;
+use game_system::prelude::prototype::Player;
+use game_system::prelude::*;
+use game_system::re_export::service::TriggerOptions;
+use game_system::runtime::exec_triggers;
+use game_system::zone::{destroy_card, draw_card, play_card};
+
+static ZONE_CHANGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn players(machine: &Machine<Wait<Start>, EmptyStack>) -> Vec<EntityId> {
+    machine
+        .entities
+        .with_proto::<Player>()
+        .map(|e| e.id())
+        .collect()
+}
+
+fn new_card(machine: &mut Machine<Wait<Start>, EmptyStack>, owner: EntityId) -> EntityId {
+    let card = machine.entities.new_entity().unwrap();
+    card.owner = Some(owner);
+    card.id()
+}
+
+#[test]
+/// Zones keep their entities ordered and respect their capacity.
+fn zone_positions() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let player = players(&machine)[0];
+    let cards: Vec<EntityId> = (0..3).map(|_| new_card(&mut machine, player)).collect();
+    for &card in &cards {
+        machine
+            .zones
+            .insert(card, player, ZoneKind::Deck, None)
+            .unwrap();
+    }
+    assert_eq!(machine.zones.entities(player, ZoneKind::Deck), &cards[..]);
+    assert_eq!(machine.zones.top(player, ZoneKind::Deck), Some(cards[2]));
+    assert_eq!(
+        machine.zones.insert(cards[0], player, ZoneKind::Hand, None),
+        Err(ZoneError::DuplicateEntity(cards[0]))
+    );
+
+    machine.zones.set_capacity(ZoneKind::Hand, 1);
+    let change = machine
+        .zones
+        .move_top(player, ZoneKind::Deck, ZoneKind::Hand)
+        .unwrap();
+    assert_eq!(change.entity, cards[2]);
+    assert_eq!(change.from.index, 2);
+    assert_eq!(change.into.zone, ZoneKind::Hand);
+    assert_eq!(
+        machine
+            .zones
+            .move_top(player, ZoneKind::Deck, ZoneKind::Hand),
+        Err(ZoneError::Full(1))
+    );
+    assert_eq!(
+        machine
+            .zones
+            .move_entity(cards[0], player, ZoneKind::Board, Some(1)),
+        Err(ZoneError::InvalidPosition(1))
+    );
+
+    // Entities can be reordered within their zone.
+    machine
+        .zones
+        .move_entity(cards[0], player, ZoneKind::Deck, Some(1))
+        .unwrap();
+    assert_eq!(
+        machine.zones.entities(player, ZoneKind::Deck),
+        &[cards[1], cards[0]]
+    );
+    let position = machine.zones.position_of(cards[0]).unwrap();
+    assert_eq!((position.owner, position.index), (player, 1));
+}
+
+fn count_zone_changes<CTS>(
+    x: Machine<Trigger<Peri, ZoneChanged>, CTS>,
+) -> Result<Machine<Trigger<Peri, ZoneChanged>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + 'static,
+{
+    ZONE_CHANGE_COUNT.fetch_add(1, Ordering::SeqCst);
+    Ok(x)
+}
+
+/// The first player draws a card, plays it and destroys it again.
+fn cycle_card<CTS>(
+    x: Machine<Trigger<Peri, EndTurn>, CTS>,
+) -> Result<Machine<Trigger<Peri, EndTurn>, CTS>, MachineError>
+where
+    CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+{
+    let player = x.entities.first_with_proto::<Player>().unwrap().id();
+    let x = draw_card(x, player)?;
+    let card = x.zones.top(player, ZoneKind::Hand).unwrap();
+    let x = play_card(x, card, None)?;
+    destroy_card(x, card)
+}
+
+#[test]
+/// Moving cards between zones causes triggers, cards leaving the board stop
+/// responding to the game.
+fn zone_change_triggers() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    machine.triggers.add_trigger(cycle_card::<AnyStack>);
+    machine.triggers.add_trigger(count_zone_changes::<AnyStack>);
+    let player = players(&machine)[0];
+    let card = new_card(&mut machine, player);
+    machine
+        .zones
+        .insert(card, player, ZoneKind::Deck, None)
+        .unwrap();
+    let options = TriggerOptions {
+        owner: Some(card),
+        ..Default::default()
+    };
+    machine
+        .triggers
+        .add_trigger_with(count_zone_changes::<AnyStack>, options);
+
+    let action: Machine<Action<Start>, _> = machine.transition(transaction::Epsilon);
+    let machine: Machine<Wait<Input>, _> = action.transition(transaction::Epsilon);
+    let action: Machine<Action<EndTurn>, _> = machine.transition(transaction::Epsilon);
+    let mut effect: Machine<Effect<EndTurn>, _> = action.pushdown(transaction::Epsilon);
+    effect = exec_triggers(effect, transaction::Epsilon).unwrap();
+
+    // The trigger owned by the card responds to the moves into the hand and
+    // onto the board.
+    assert_eq!(ZONE_CHANGE_COUNT.load(Ordering::SeqCst), 5);
+    assert_eq!(effect.zones.entities(player, ZoneKind::Graveyard), &[card]);
+    assert_eq!(effect.triggers.retrieve_triggers_owned_by(card).count(), 0);
+}
+
+/// Tries to play the provided card and returns the reason it failed.
+fn play_error(machine: Machine<Wait<Start>, EmptyStack>, card: EntityId) -> Option<ZoneError> {
+    let (_, parts) = machine.into_parts();
+    let machine: Machine<Trigger<Peri, Start>, EmptyStack> =
+        unsafe { Machine::from_parts(transaction::Epsilon, parts) };
+    let error = play_card(machine, card, None).err().unwrap();
+    assert_eq!(error.kind(), &ErrorKind::ConstraintError);
+    error
+        .cause()
+        .and_then(|c| c.downcast_ref::<ZoneError>())
+        .cloned()
+}
+
+#[test]
+/// Only cards within the hand of their owner can be played.
+fn play_card_outside_hand() {
+    let mut machine = Machine::new(&Default::default()).unwrap();
+    let player_ids = players(&machine);
+    let in_deck = new_card(&mut machine, player_ids[0]);
+    machine
+        .zones
+        .insert(in_deck, player_ids[0], ZoneKind::Deck, None)
+        .unwrap();
+    let foreign_hand = new_card(&mut machine, player_ids[0]);
+    machine
+        .zones
+        .insert(foreign_hand, player_ids[1], ZoneKind::Hand, None)
+        .unwrap();
+    let nowhere = new_card(&mut machine, player_ids[0]);
+
+    assert_eq!(
+        play_error(machine.clone(), in_deck),
+        Some(ZoneError::InvalidSource(in_deck))
+    );
+    assert_eq!(
+        play_error(machine.clone(), foreign_hand),
+        Some(ZoneError::InvalidSource(foreign_hand))
+    );
+    assert_eq!(
+        play_error(machine, nowhere),
+        Some(ZoneError::MissingEntity(nowhere))
+    );
+}
@@ -21,8 +21,19 @@ pub type EmptyStack = ();
 pub type AnyStack = ();
 
 /// Usability macro for pushing a new type onto the CTStack.
+///
+/// `ct!(B => CTS, from A)` additionally records that the machine was pushed down
+/// from state A into state B, see [`CTStack::Origin`].
 #[macro_export]
 macro_rules! ct {
+    // Push new item, and the state it was pushed down from, onto the provided stack.
+    ($new_item:ty => $stack:ty, from $origin:ty) => {
+        (
+            $stack,
+            $crate::ctstack::ZeroSizedType<$new_item>,
+            $crate::ctstack::ZeroSizedType<$origin>,
+        )
+    };
     // Push new item onto the provided stack.
     ($new_item:ty => $stack:ty) => {
         ($stack, $crate::ctstack::ZeroSizedType<$new_item>)
@@ -37,17 +48,34 @@ pub trait CTStack {
     type Head;
     /// Type of the next to front type on the stack.
     type Tail: CTStack;
+    /// Type of the state the machine was pushed down from when the front type
+    /// was pushed onto the stack.
+    type Origin;
+
+    /// Returns the names of all states on the stack, the bottom of the stack
+    /// is returned first.
+    fn state_names() -> Vec<String>;
 }
 
 impl CTStack for ! {
     type Head = !;
     type Tail = !;
+    type Origin = !;
+
+    fn state_names() -> Vec<String> {
+        vec![]
+    }
 }
 
 // Not wrapped into PhantomData because '()' is already a zero sized type.
 impl CTStack for () {
     type Head = ();
     type Tail = !;
+    type Origin = !;
+
+    fn state_names() -> Vec<String> {
+        vec![]
+    }
 }
 
 // X wrapped in PhantomData to make it zero-sized.
@@ -57,6 +85,11 @@ where
 {
     type Head = X;
     type Tail = ();
+    type Origin = !;
+
+    fn state_names() -> Vec<String> {
+        vec![X::name()]
+    }
 }
 
 // X wrapped in PhantomData to make it zero-sized.
@@ -68,4 +101,31 @@ where
 {
     type Head = X;
     type Tail = S;
+    // The origin of this item is unknown.
+    type Origin = !;
+
+    fn state_names() -> Vec<String> {
+        let mut names = S::state_names();
+        names.push(X::name());
+        names
+    }
+}
+
+// X and O wrapped in PhantomData to make them zero-sized.
+// This implementation supposes that S is already a zero-sized stack.
+impl<S, X, O> CTStack for (S, PhantomData<X>, PhantomData<O>)
+where
+    S: CTStack,
+    X: State + marker::TopLevel,
+    O: State + marker::TopLevel,
+{
+    type Head = X;
+    type Tail = S;
+    type Origin = O;
+
+    fn state_names() -> Vec<String> {
+        let mut names = S::state_names();
+        names.push(X::name());
+        names
+    }
 }
@@ -1,14 +1,36 @@
 //! Types, to be used within the system, providing context of unexpected behaviour.
 
+use std::any::Any;
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Display, Formatter};
 use std::string::ToString;
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
 
 use failure::{Backtrace, Context, Error, Fail};
 
-use function::StateContainer;
+use function::{EntityId, ServiceCompliance, State, StateContainer};
 use marker;
+use service::trigger::TriggerService;
+
+/// Machine stored within a [`MachineError`], its type is only known again when
+/// retrieving it.
+trait ErasedMachine: Debug + Send {
+    fn as_any(&self) -> &Any;
+    fn into_any(self: Box<Self>) -> Box<Any + Send>;
+}
+
+impl<M> ErasedMachine for M
+where
+    M: Debug + Send + 'static,
+{
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<Any + Send> {
+        self
+    }
+}
 
 /// User facing error type indicating a failure during evalutation/computation of the
 /// state machine.
@@ -19,9 +41,80 @@ use marker;
 #[derive(Debug)]
 pub struct MachineError {
     // Debug + Send becomes Debug + Send + Sync when wrapped in the
-    // Arc<Mutex<_>> combo!
-    machine: Arc<Mutex<Debug + Send>>,
+    // Mutex<_>!
+    machine: Mutex<Box<ErasedMachine>>,
+    // Name of the state the machine was in when the failure occurred.
+    state: String,
     inner: Context<ErrorKind>,
+    // Amount of triggers, and nested trigger chains, which were executing on the
+    // stored machine and got aborted by this error.
+    aborted_triggers: usize,
+    aborted_recursions: usize,
+}
+
+impl MachineError {
+    /// Returns the human readable name of the state the machine was in when
+    /// the failure occurred.
+    pub fn state_name(&self) -> &str {
+        &self.state
+    }
+
+    /// Returns the kind of failure.
+    pub fn kind(&self) -> &ErrorKind {
+        self.inner.get_context()
+    }
+
+    /// Registers that the execution of a trigger was aborted by this error.
+    pub fn abort_trigger(mut self) -> Self {
+        self.aborted_triggers += 1;
+        self
+    }
+
+    /// Registers that the execution of a nested trigger chain was aborted by this error.
+    pub fn abort_recursion(mut self) -> Self {
+        self.aborted_recursions += 1;
+        self
+    }
+
+    /// Retrieves the machine snapshot stored within this error.
+    ///
+    /// All aborted triggers and trigger chains are unregistered from the [`TriggerService`]
+    /// of the machine, so it can execute triggers again.
+    /// The error itself is returned when the stored machine is not of the requested type.
+    pub fn into_machine<M>(self) -> Result<M, Self>
+    where
+        M: StateContainer
+            + ServiceCompliance<TriggerService<M::TimingEnum, M::TriggerEnum>>
+            + 'static,
+        M::TimingEnum: PartialEq + Copy,
+        M::TriggerEnum: PartialEq + Copy,
+    {
+        let matches = match self.machine.lock() {
+            Ok(machine) => machine.as_any().is::<M>(),
+            Err(poisoned) => poisoned.into_inner().as_any().is::<M>(),
+        };
+        if !matches {
+            return Err(self);
+        }
+
+        let (aborted_triggers, aborted_recursions) =
+            (self.aborted_triggers, self.aborted_recursions);
+        let machine = self.machine.into_inner().unwrap_or_else(|e| e.into_inner());
+        let mut machine = *machine
+            .into_any()
+            .downcast::<M>()
+            .expect("The machine type was verified");
+        {
+            let triggers = ServiceCompliance::get_mut(&mut machine);
+            for _ in 0..aborted_triggers {
+                triggers.exit_trigger();
+            }
+            for _ in 0..aborted_recursions {
+                triggers.exit_recursion();
+            }
+        }
+        Ok(machine)
+    }
 }
 
 impl Fail for MachineError {
@@ -36,7 +129,7 @@ impl Fail for MachineError {
 
 impl Display for MachineError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Display::fmt(&self.inner, f)
+        write!(f, "{} (in state `{}`)", self.inner, self.state)
     }
 }
 
@@ -53,6 +146,10 @@ pub enum ErrorKind {
     /// Error allowing to print an entirely custom message
     #[fail(display = "{:}", _0)]
     Custom(Cow<'static, str>),
+    /// Error indicating nested trigger chains exceeded the configured limit.
+    /// The names of the executing triggers are stored, outermost first.
+    #[fail(display = "Trigger recursion limit reached through {:?}", _0)]
+    RecursionLimit(Vec<String>),
 }
 
 /// TODO
@@ -70,11 +167,15 @@ impl HydratedErrorExt for Error {
         I: FnOnce() -> M,
         M: StateContainer + Debug + Send + 'static,
     {
+        let machine = (machine)();
         MachineError {
-            machine: Arc::new(Mutex::new((machine)())),
+            state: <M::State as State>::name(),
+            machine: Mutex::new(Box::new(machine)),
             // TODO; Figure out how to reach the original error and
             // build a context from there.
             inner: self.context(ErrorKind::LogicError),
+            aborted_triggers: 0,
+            aborted_recursions: 0,
         }
     }
 }
@@ -125,11 +226,15 @@ where
         M: StateContainer + Debug + Send + 'static,
     {
         // Build and return custom error type
+        let machine = (machine)();
         MachineError {
-            machine: Arc::new(Mutex::new((machine)())),
+            state: <M::State as State>::name(),
+            machine: Mutex::new(Box::new(machine)),
             // Build new context for our own error kind.
             // and chain the previous one..
             inner: self.context(context),
+            aborted_triggers: 0,
+            aborted_recursions: 0,
         }
     }
 
@@ -140,10 +245,13 @@ where
     {
         // Build and return custom error type
         MachineError {
-            machine: Arc::new(Mutex::new(machine)),
+            state: <M::State as State>::name(),
+            machine: Mutex::new(Box::new(machine)),
             // Build new context for our own error kind.
             // and chain the previous one..
             inner: self.context(ErrorKind::Custom(msg.into())),
+            aborted_triggers: 0,
+            aborted_recursions: 0,
         }
     }
 }
@@ -180,10 +288,19 @@ pub mod custom_type {
         }
     }
 
-    /// Code failed to push a new item onto the chosen stack.
+    /// Code failed to unpack a transaction from it's container.
     #[derive(Debug, Fail)]
-    #[fail(display = "Error unpacking the provided transaction")]
-    pub struct TransactionUnpackError;
+    #[fail(
+        display = "Error unpacking the provided transaction, expected `{:}` but found `{:}`",
+        expected,
+        factual
+    )]
+    pub struct TransactionUnpackError {
+        /// Name of the variant which was expected to be unpacked.
+        pub expected: &'static str,
+        /// Name of the variant which was actually contained.
+        pub factual: &'static str,
+    }
 
     /// Code failed to push a new item onto the chosen stack.
     #[derive(Debug, Fail)]
@@ -233,6 +350,56 @@ pub mod custom_type {
         }
     }
 
+    /// Error thrown when multiple entities can't be borrowed mutably at the same time.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntityAccessError<ID>
+    where
+        ID: Display + Debug,
+    {
+        /// The entity with the contained id was not found.
+        Missing(ID),
+        /// The contained id was requested more than once.
+        Duplicate(ID),
+        /// The entity with the contained id doesn't have the requested prototype.
+        MissingPrototype(ID),
+    }
+
+    impl<ID> Fail for EntityAccessError<ID>
+    where
+        ID: Display + Debug + Send + Sync + 'static,
+    {
+    }
+
+    impl<ID> fmt::Display for EntityAccessError<ID>
+    where
+        ID: Display + Debug,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match *self {
+                EntityAccessError::Missing(ref id) => {
+                    write!(f, "The entity with id `{:}` was not found", id)
+                }
+                EntityAccessError::Duplicate(ref id) => {
+                    write!(f, "The entity with id `{:}` is requested more than once", id)
+                }
+                EntityAccessError::MissingPrototype(ref id) => write!(
+                    f,
+                    "The entity with id `{:}` doesn't have the requested prototype",
+                    id
+                ),
+            }
+        }
+    }
+
+    impl<ID> From<MissingEntityError<ID>> for EntityAccessError<ID>
+    where
+        ID: Display + Debug,
+    {
+        fn from(x: MissingEntityError<ID>) -> Self {
+            EntityAccessError::Missing(x.0)
+        }
+    }
+
     /// Specific error thrown when the requested card-id is not known.
     #[derive(Debug)]
     pub struct MissingCardError<ID>(pub ID)
@@ -310,6 +477,55 @@ pub mod custom_type {
         }
     }
 
+    /// Specific error thrown when the requested trigger name is not registered.
+    #[derive(Debug)]
+    pub struct MissingTriggerError<ID>(pub ID)
+    where
+        ID: Display + Debug;
+
+    impl<ID> Fail for MissingTriggerError<ID>
+    where
+        ID: Display + Debug + Send + Sync + 'static,
+    {
+    }
+
+    impl<ID> fmt::Display for MissingTriggerError<ID>
+    where
+        ID: Display + Debug,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "The trigger with name `{:}` is not registered", self.0)
+        }
+    }
+
+    /// Error thrown when nested trigger chains exceed the recursion limit.
+    #[derive(Debug, Fail)]
+    #[fail(display = "Exceeded the limit of {:} nested trigger chains", _0)]
+    pub struct RecursionLimitError(pub usize);
+
+    /// Enumeration of failure cases working with zones.
+    #[derive(Debug, Fail, Copy, Clone, Eq, PartialEq)]
+    pub enum ZoneError {
+        /// Error indicating the zone cannot hold more entities.
+        #[fail(display = "The zone is full, it holds at most {:} entities", _0)]
+        Full(usize),
+        /// Error indicating the requested position is outside the zone.
+        #[fail(display = "Position {:} is outside of the zone", _0)]
+        InvalidPosition(usize),
+        /// Error indicating the zone holds no entities.
+        #[fail(display = "The zone is empty")]
+        Empty,
+        /// Error indicating the entity is not within any zone.
+        #[fail(display = "The entity with id `{:}` is not within any zone", _0)]
+        MissingEntity(EntityId),
+        /// Error indicating the entity is already within a zone.
+        #[fail(display = "The entity with id `{:}` is already within a zone", _0)]
+        DuplicateEntity(EntityId),
+        /// Error indicating the entity cannot be moved out of the zone it's within.
+        #[fail(display = "The entity with id `{:}` cannot be moved from it's current zone", _0)]
+        InvalidSource(EntityId),
+    }
+
     /// Error thrown when the provided object's ID collides with an already known ID.
     #[derive(Debug)]
     pub struct IDCollisionError<ID>(pub ID)
@@ -331,6 +547,40 @@ pub mod custom_type {
         }
     }
 
+    /// Error thrown when a set of cards can't be installed.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum CardSetError<ID>
+    where
+        ID: Display + Debug,
+    {
+        /// A set with the contained identifier is already installed.
+        DuplicateSet(u32),
+        /// A card with the contained id is already registered.
+        DuplicateCard(ID),
+    }
+
+    impl<ID> Fail for CardSetError<ID>
+    where
+        ID: Display + Debug + Send + Sync + 'static,
+    {
+    }
+
+    impl<ID> fmt::Display for CardSetError<ID>
+    where
+        ID: Display + Debug,
+    {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match *self {
+                CardSetError::DuplicateSet(ref id) => {
+                    write!(f, "The card set with id `{:}` is already installed", id)
+                }
+                CardSetError::DuplicateCard(ref id) => {
+                    write!(f, "The card with id `{:}` is already registered", id)
+                }
+            }
+        }
+    }
+
     /// Enumeration of failure cases working with [`Trigger`]s.
     #[derive(Debug, Fail, Copy, Clone, Eq, PartialEq)]
     pub enum TriggerFail {
@@ -341,5 +591,14 @@ pub mod custom_type {
         /// constraints.
         #[fail(display = "The provided machine does not validate on the constraints")]
         ConstraintFail,
+        /// Error indicating the callback is not known to the trigger registry.
+        #[fail(display = "The callback is not registered within the trigger registry")]
+        Unregistered,
+        /// Error indicating the trigger was built for another state machine.
+        #[fail(display = "The trigger was built for another state machine")]
+        MachineMismatch,
+        /// Error indicating the closure of the trigger was built for another machine type.
+        #[fail(display = "The trigger closure was built for another machine type")]
+        CallbackMismatch,
     }
 }
@@ -9,6 +9,11 @@ use marker;
 ///
 /// This container of states could be reworded as 'the state machine' itself.
 pub trait StateContainer {
+    /// Type identifying the state machine regardless of it's current state.
+    ///
+    /// All states of one machine share the same family, this is used to verify that
+    /// type-erased items, like triggers, are used with the machine they were built for.
+    type Family: 'static;
     /// Type of the current state held by the state machine.
     type State: State;
     /// Type of transaction object necessary to transition into the
@@ -21,6 +26,33 @@ pub trait StateContainer {
     /// Type representing the stack of types where the container state was
     /// transitioned in a pushdown manner.
     type TransitionRecord: CTStack;
+    /// Type of the same machine in the same state, with its transition history
+    /// erased into [`AnyStack`].
+    ///
+    /// Type-erased items built for the machine, like closure triggers, are stored
+    /// for this type so they can be verified for any transition history.
+    type Normalized;
+
+    /// Converts this machine into its normalized form, see [`StateContainer::Normalized`].
+    fn into_normalized(self) -> Self::Normalized
+    where
+        Self: Sized;
+
+    /// Rebuilds this machine from its normalized form.
+    fn from_normalized(x: Self::Normalized) -> Self
+    where
+        Self: Sized;
+
+    /// Returns the human readable name of the current state.
+    fn state_name(&self) -> String {
+        <Self::State as State>::name()
+    }
+
+    /// Returns the names of all states recorded within the transition history, the
+    /// oldest state is returned first.
+    fn history_names(&self) -> Vec<String> {
+        <Self::TransitionRecord as CTStack>::state_names()
+    }
 }
 
 /// Trait generalizing over any state that's present in the state machine.
@@ -28,6 +60,13 @@ pub trait State {
     /// Type of structure which must be provided when transitioning into the state
     /// represented by the enclosing type.
     type Transaction: marker::Transaction;
+
+    /// Returns the human readable name of the state.
+    ///
+    /// States which are generic over other states compose their name from the
+    /// names of their arguments, eg `Trigger<Peri, EndTurn>`.
+    /// `#[derive(State)]` from medici_derive generates this name.
+    fn name() -> String;
 }
 
 /// Trait generalizing over any state which is used to bootstrap an execution of triggers.
@@ -109,6 +148,28 @@ pub trait CardBuilder<C: Card> {
     fn new_with_id<I: Into<C::ID>>(id: I) -> C;
 }
 
+/// Trait representing a collection of [`Card`]s which are distributed together.
+///
+/// Sets are installed into a [`CardService`] as a whole.
+pub trait CardSet: fmt::Debug {
+    /// The type of cards contained by this set.
+    type Card: Card;
+
+    /// Returns the identifier of this set, which is unique across all sets.
+    fn set_id(&self) -> u32;
+
+    /// Returns the human readable name of this set.
+    fn name(&self) -> &'static str;
+
+    /// Returns the version of this set.
+    ///
+    /// The version is incremented each time cards of the set are altered.
+    fn version(&self) -> u32;
+
+    /// Returns all cards contained by this set.
+    fn cards(&self) -> Vec<Self::Card>;
+}
+
 /// Trait for implementing a certain service on the state machine.
 ///
 /// Because of this design exactly one object of each service type can be hooked onto
@@ -166,7 +227,8 @@ pub trait ArrayStorageCompliance {
 
 /* ID sructures */
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Type that's generally used to identify and order [`Card`] objects.
 ///
 /// The first numeric element is the SET IDENTIFIER.
@@ -189,4 +251,14 @@ impl CardId {
     pub fn from_set<S: Into<u32>>(set: S, id: u32) -> Self {
         CardId(set.into(), id)
     }
+
+    /// Returns the identifier of the set this card belongs to.
+    pub fn set(&self) -> u32 {
+        self.0
+    }
+
+    /// Returns the ordinal identifier of this card within it's set.
+    pub fn ordinal(&self) -> u32 {
+        self.1
+    }
 }
@@ -32,6 +32,11 @@ pub extern crate failure;
 extern crate failure_derive;
 #[macro_use]
 extern crate maplit;
+#[cfg(feature = "serde-support")]
+extern crate serde;
+#[cfg(feature = "serde-support")]
+#[macro_use]
+extern crate serde_derive;
 
 mod workaround;
 
@@ -46,6 +51,7 @@ pub mod error;
 pub mod function;
 pub mod marker;
 pub mod service;
+#[macro_use]
 pub mod stm;
 pub mod storage;
 pub mod transaction;
@@ -1,4 +1,5 @@
 //! Module containing items to work with game cards.
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::Hash;
@@ -29,7 +30,7 @@ where
 {
     uid: CardId,
     /// Holds the name of this card.
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Holds the properties attributed to this card.
     pub state: HashMap<S, u32>,
     /// Holds all triggers registered on this card.
@@ -70,7 +71,7 @@ where
     fn new_with_id<I: Into<CardId>>(id: I) -> Self {
         Self {
             uid: id.into(),
-            name: "[MISSING NAME]",
+            name: "[MISSING NAME]".into(),
             state: hashmap!{},
             triggers: TriggerService::new(),
             _phantom: PhantomData,
@@ -7,7 +7,7 @@ use std::hash::Hash;
 use value_from_type_traits::IntoEnum;
 
 use error::custom_type::{MissingPropertyError, MissingPrototypeError};
-use function::{self, EntityBuilder, EntityId};
+use function::{self, CardId, EntityBuilder, EntityId};
 use marker;
 
 use prefab::prototype::ProtoItem;
@@ -20,6 +20,7 @@ pub const GAME_E_ID: EntityId = 0;
 pub type Entity = EntityStruct<i32, ProtoItem>;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Type representing a stateful 'thing' within the state-machine.
 ///
 /// Essentially everything can be classified as an entity because there are
@@ -44,6 +45,13 @@ where
     ///     Name of player entities,
     ///     Short identity for debugging purposes.
     pub human_readable: Option<String>,
+    /// Identifier of the card this entity was built from.
+    ///
+    /// Rules can use this to look up the name, base properties and triggers of
+    /// the entity.
+    pub card_id: Option<CardId>,
+    /// The entity controlling this entity, eg the player who owns the card.
+    pub owner: Option<EntityId>,
 }
 
 impl<S, P> function::Identifiable for EntityStruct<S, P>
@@ -76,6 +84,8 @@ where
             state: hashmap!{},
             prototypes: hashset!{},
             human_readable: None,
+            card_id: None,
+            owner: None,
         }
     }
 }
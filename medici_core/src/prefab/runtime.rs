@@ -9,14 +9,22 @@ use std::fmt::Debug;
 use error::*;
 use function::{ServiceCompliance, StateContainer, TriggerState};
 use marker;
-use service::trigger::{TriggerService, TriggerWrapper};
+use service::trigger::{TriggerOrderContext, TriggerService, TriggerWrapper};
 use storage::trigger::UnsafeTrigger;
 
 /// Extract all triggers from the provided machine for matching
 /// conditions.
+///
+/// Triggers which have a condition attached are only returned if that condition
+/// holds for the provided machine.
+/// Triggers which cannot be unpacked for the provided machine are returned as well,
+/// [`exec_trigger_stepped`] reports them as [`TriggerFail`].
 pub fn fetch_triggers<'a, M, ETM, ETR>(machine: &M) -> Vec<UnsafeTrigger<ETM, ETR>>
 where
-    M: StateContainer + ServiceCompliance<TriggerService<ETM, ETR>>,
+    M: StateContainer
+        + ServiceCompliance<TriggerService<ETM, ETR>>
+        + TriggerOrderContext
+        + 'static,
     <M as StateContainer>::State: TriggerState,
     <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
     <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
@@ -25,12 +33,19 @@ where
 {
     ServiceCompliance::get(machine)
         .retrieve_triggers(machine)
+        // Note(UB):
+        // The condition pointer is transmuted with the provided machine as template,
+        // see [`TriggerWrapper::try_from_trigger_entry`].
+        .filter(|t| unsafe { TriggerWrapper::<M, ETM, ETR>::condition_holds(t, machine) })
         .cloned()
         .collect()
 }
 
 /// Executes all passed down triggers for the provided machine.
 ///
+/// The name of each trigger is registered on the [`TriggerService`] while it executes,
+/// see [`TriggerService::active_chain`].
+///
 /// # Unsafe
 /// Verify that each provided [`UnsafeTrigger`] has been specifically
 /// created for the provided machine!
@@ -39,7 +54,14 @@ pub unsafe fn exec_trigger_stepped<'a, 'b, M, TM, TR, ETM, ETR, I>(
     triggers: I,
 ) -> Result<M, MachineError>
 where
-    M: StateContainer + Borrow<M> + Debug + Clone + Send + Sync + 'static,
+    M: StateContainer
+        + ServiceCompliance<TriggerService<ETM, ETR>>
+        + Borrow<M>
+        + Debug
+        + Clone
+        + Send
+        + Sync
+        + 'static,
     M::State: TriggerState<Timing = TM, Trigger = TR>,
     TM: marker::Timing + IntoEnum<ETM>,
     TR: marker::Triggerable + IntoEnum<ETR>,
@@ -54,17 +76,28 @@ where
         // Because the StateContainer type is erased, execution of the wrapped
         // trigger could lead to UB when a wrong trigger has been inserted.
         unsafe {
+            ServiceCompliance::get_mut(&mut machine).enter_trigger(&t);
+
             // Try to build a valid trigger for the specified machine.
             // This will be reported back up to the caller on failure.
             let wrapper = match TriggerWrapper::try_from_trigger_entry(t) {
                 Ok(v) => v,
-                Err(e) => return Err(e.infuse(ErrorKind::ConstraintError, || machine)),
+                Err(e) => {
+                    ServiceCompliance::get_mut(&mut machine).exit_trigger();
+                    return Err(e.infuse(ErrorKind::ConstraintError, || machine));
+                }
             };
 
             // Execute trigger on the machine.
             // The machine is consumed and a new one is returned. This sequence happens for each
             // trigger.
-            machine = (wrapper.into_callback())(machine)?;
+            // The machine stored within a failure is still registering this trigger, the
+            // error unregisters it when the machine is retrieved.
+            machine = wrapper
+                .into_callback()
+                .call(machine)
+                .map_err(|e| e.abort_trigger())?;
+            ServiceCompliance::get_mut(&mut machine).exit_trigger();
         }
     }
 
@@ -89,7 +122,7 @@ macro_rules! build_exec_triggers_checked {
         use $crate::marker;
         use $crate::prefab::runtime::{exec_trigger_stepped, fetch_triggers};
         use $crate::prefab::state::{Effect, Trigger};
-        use $crate::service::trigger::TriggerService;
+        use $crate::service::trigger::{TriggerOrderContext, TriggerService};
 
         #[doc(hidden)]
         mod _shorten_syntax {
@@ -129,6 +162,7 @@ macro_rules! build_exec_triggers_checked {
             M2<TR, CTS>: StateContainer<TimingEnum = ETM, TriggerEnum = ETR>
                 + TransitionInto<M3<TR, CTS>, CTS>
                 + ServiceCompliance<TriggerService<ETM, ETR>>
+                + TriggerOrderContext
                 + Debug
                 + Clone
                 + Send
@@ -141,6 +175,7 @@ macro_rules! build_exec_triggers_checked {
             M3<TR, CTS>: StateContainer<TimingEnum = ETM, TriggerEnum = ETR>
                 + TransitionInto<M4<TR, CTS>, CTS>
                 + ServiceCompliance<TriggerService<ETM, ETR>>
+                + TriggerOrderContext
                 + Debug
                 + Clone
                 + Send
@@ -153,6 +188,7 @@ macro_rules! build_exec_triggers_checked {
             M4<TR, CTS>: StateContainer<TimingEnum = ETM, TriggerEnum = ETR>
                 + TransitionInto<M1<TR, CTS>, CTS>
                 + ServiceCompliance<TriggerService<ETM, ETR>>
+                + TriggerOrderContext
                 + Debug
                 + Clone
                 + Send
@@ -185,3 +221,182 @@ macro_rules! build_exec_triggers_checked {
         }
     };
 }
+
+/// Macro used for building a function that automatically constructs a method called [`exec_recurse`].
+///
+/// The constructed method will pushdown from any [`Trigger`] state into [`RecurseEffect`] of the
+/// requested [`Triggerable`], execute the triggers of each timing of that triggerable and pull back
+/// up into the original [`Trigger`] state.
+/// Each step is a pushdown, so the transition history of the machine statically guarantees the
+/// machine returns into the state it started from.
+///
+/// The second argument is the [`TransactionContainer`] of the machine.
+///
+/// The amount of nested recursions is limited by [`TriggerService::recursion_limit`]. Exceeding
+/// that limit results in an error of kind [`ErrorKind::RecursionLimit`], holding the names of
+/// the triggers which caused the recursion.
+///
+/// This macro can be invoked next to [`build_exec_triggers_checked`] within the same module.
+#[macro_export]
+macro_rules! build_exec_recurse_checked {
+    ($container_name:ident, $transaction_container:ty) => {
+        #[doc(hidden)]
+        mod _exec_recurse {
+            // Since we're in macro space, we have no access to the std prelude!
+            use std::convert::TryFrom;
+            use std::fmt::Debug;
+            use std::result::Result;
+            use $crate::value_from_type_traits::IntoEnum;
+
+            use super::*;
+            use $crate::ctstack::CTStack;
+            use $crate::error::{ErrorKind, FrontendErrorExt, MachineError};
+            use $crate::function::{ServiceCompliance, State, StateContainer, TriggerState};
+            use $crate::marker;
+            use $crate::prefab::runtime::{exec_trigger_stepped, fetch_triggers};
+            use $crate::prefab::state::{RecurseEffect, Trigger};
+            use $crate::prefab::timing::{Peri, Post, Pre};
+            use $crate::service::trigger::{TriggerOrderContext, TriggerService};
+            use $crate::stm::checked::{PullupInto, PushdownInto};
+
+            type TTC = $transaction_container;
+
+            type S1<X, TM, TR, CTS> =
+                $crate::ct!(RecurseEffect<X> => CTS, from Trigger<TM, TR>);
+            type S2<X, TM, TR, CTS> =
+                $crate::ct!(Trigger<Pre, X> => S1<X, TM, TR, CTS>, from RecurseEffect<X>);
+            type S3<X, TM, TR, CTS> =
+                $crate::ct!(Trigger<Peri, X> => S2<X, TM, TR, CTS>, from Trigger<Pre, X>);
+            type S4<X, TM, TR, CTS> =
+                $crate::ct!(Trigger<Post, X> => S3<X, TM, TR, CTS>, from Trigger<Peri, X>);
+
+            type R0<TM, TR, CTS> = $container_name<Trigger<TM, TR>, CTS>;
+            type R1<X, TM, TR, CTS> = $container_name<RecurseEffect<X>, S1<X, TM, TR, CTS>>;
+            type R2<X, TM, TR, CTS> = $container_name<Trigger<Pre, X>, S2<X, TM, TR, CTS>>;
+            type R3<X, TM, TR, CTS> = $container_name<Trigger<Peri, X>, S3<X, TM, TR, CTS>>;
+            type R4<X, TM, TR, CTS> = $container_name<Trigger<Post, X>, S4<X, TM, TR, CTS>>;
+
+            /// Takes the provided machine (in any [`Trigger`] state) and executes all triggers
+            /// caused by the triggerable X.
+            ///
+            /// The provided transaction is used for each state within the recursion.
+            pub fn exec_recurse<X, TM, TR, CTS, XT, ETM, ETR>(
+                machine: R0<TM, TR, CTS>,
+                transaction: XT,
+            ) -> Result<R0<TM, TR, CTS>, MachineError>
+            where
+                CTS: CTStack + Debug + Clone + Send + Sync + 'static,
+                TM: marker::Timing + State + Send + 'static,
+                TR: marker::Triggerable + State + Send + 'static,
+                <TR as State>::Transaction: TryFrom<TTC>,
+                X: marker::Triggerable + State<Transaction = XT> + IntoEnum<ETR> + Send + 'static,
+                XT: marker::Transaction + Into<TTC> + TryFrom<TTC>,
+                ETM: marker::TimingEnumerator + PartialEq + Copy,
+                ETR: marker::TriggerEnumerator + PartialEq + Copy,
+                //
+                R0<TM, TR, CTS>: StateContainer<TransitionRecord = CTS>
+                    + PushdownInto<R1<X, TM, TR, CTS>, S1<X, TM, TR, CTS>, TTC>,
+
+                R1<X, TM, TR, CTS>: StateContainer<TransitionRecord = S1<X, TM, TR, CTS>>
+                    + PushdownInto<R2<X, TM, TR, CTS>, S2<X, TM, TR, CTS>, TTC>
+                    + PullupInto<R0<TM, TR, CTS>, S1<X, TM, TR, CTS>, TTC>,
+                <R1<X, TM, TR, CTS> as StateContainer>::State: State<Transaction = XT>,
+
+                R2<X, TM, TR, CTS>: StateContainer<
+                    TimingEnum = ETM,
+                    TriggerEnum = ETR,
+                    TransitionRecord = S2<X, TM, TR, CTS>,
+                > + ServiceCompliance<TriggerService<ETM, ETR>>
+                    + TriggerOrderContext
+                    + PushdownInto<R3<X, TM, TR, CTS>, S3<X, TM, TR, CTS>, TTC>
+                    + PullupInto<R1<X, TM, TR, CTS>, S2<X, TM, TR, CTS>, TTC>
+                    + Debug
+                    + Clone
+                    + Send
+                    + Sync
+                    + 'static,
+                <R2<X, TM, TR, CTS> as StateContainer>::State:
+                    State<Transaction = XT> + TriggerState<Trigger = X>,
+                <<R2<X, TM, TR, CTS> as StateContainer>::State as TriggerState>::Timing:
+                    IntoEnum<ETM>,
+
+                R3<X, TM, TR, CTS>: StateContainer<
+                    TimingEnum = ETM,
+                    TriggerEnum = ETR,
+                    TransitionRecord = S3<X, TM, TR, CTS>,
+                > + ServiceCompliance<TriggerService<ETM, ETR>>
+                    + TriggerOrderContext
+                    + PushdownInto<R4<X, TM, TR, CTS>, S4<X, TM, TR, CTS>, TTC>
+                    + PullupInto<R2<X, TM, TR, CTS>, S3<X, TM, TR, CTS>, TTC>
+                    + Debug
+                    + Clone
+                    + Send
+                    + Sync
+                    + 'static,
+                <R3<X, TM, TR, CTS> as StateContainer>::State:
+                    State<Transaction = XT> + TriggerState<Trigger = X>,
+                <<R3<X, TM, TR, CTS> as StateContainer>::State as TriggerState>::Timing:
+                    IntoEnum<ETM>,
+
+                R4<X, TM, TR, CTS>: StateContainer<
+                    TimingEnum = ETM,
+                    TriggerEnum = ETR,
+                    TransitionRecord = S4<X, TM, TR, CTS>,
+                > + ServiceCompliance<TriggerService<ETM, ETR>>
+                    + TriggerOrderContext
+                    + PullupInto<R3<X, TM, TR, CTS>, S4<X, TM, TR, CTS>, TTC>
+                    + Debug
+                    + Clone
+                    + Send
+                    + Sync
+                    + 'static,
+                <R4<X, TM, TR, CTS> as StateContainer>::State:
+                    State<Transaction = XT> + TriggerState<Trigger = X>,
+                <<R4<X, TM, TR, CTS> as StateContainer>::State as TriggerState>::Timing:
+                    IntoEnum<ETM>,
+            {
+                let recurse: R1<X, TM, TR, CTS> = machine.pushdown(transaction);
+
+                // Pre
+                let mut pre: R2<X, TM, TR, CTS> = recurse.pushdown(transaction);
+                let entered = ServiceCompliance::get_mut(&mut pre).enter_recursion();
+                if let Result::Err(e) = entered {
+                    let chain = ServiceCompliance::get(&pre).active_chain();
+                    return Result::Err(e.infuse(ErrorKind::RecursionLimit(chain), || pre));
+                }
+                // Failures abort this chain, which is unregistered when the machine is
+                // retrieved from the error.
+                let listeners = fetch_triggers(&pre);
+                // IMMUT REBIND
+                let pre = unsafe {
+                    exec_trigger_stepped(pre, listeners).map_err(|e| e.abort_recursion())?
+                };
+
+                // Peri
+                let peri: R3<X, TM, TR, CTS> = pre.pushdown(transaction);
+                let listeners = fetch_triggers(&peri);
+                // IMMUT REBIND
+                let peri = unsafe {
+                    exec_trigger_stepped(peri, listeners).map_err(|e| e.abort_recursion())?
+                };
+
+                // Post
+                let post: R4<X, TM, TR, CTS> = peri.pushdown(transaction);
+                let listeners = fetch_triggers(&post);
+                // IMMUT REBIND
+                let mut post = unsafe {
+                    exec_trigger_stepped(post, listeners).map_err(|e| e.abort_recursion())?
+                };
+                ServiceCompliance::get_mut(&mut post).exit_recursion();
+
+                // Unwind the recursion, each pullup is verified against the history.
+                let peri: R3<X, TM, TR, CTS> = post.pullup()?;
+                let pre: R2<X, TM, TR, CTS> = peri.pullup()?;
+                let recurse: R1<X, TM, TR, CTS> = pre.pullup()?;
+                recurse.pullup()
+            }
+        }
+
+        pub use self::_exec_recurse::exec_recurse;
+    };
+}
@@ -23,6 +23,10 @@ where
     W: marker::Waitable + State,
 {
     type Transaction = <W as State>::Transaction;
+
+    fn name() -> String {
+        format!("Wait<{}>", W::name())
+    }
 }
 
 impl<W> marker::TopLevel for Wait<W>
@@ -39,6 +43,10 @@ where
     A: marker::Actionable + State,
 {
     type Transaction = <A as State>::Transaction;
+
+    fn name() -> String {
+        format!("Action<{}>", A::name())
+    }
 }
 
 impl<A> marker::TopLevel for Action<A>
@@ -55,6 +63,10 @@ where
 pub struct Finished();
 impl State for Finished {
     type Transaction = Epsilon;
+
+    fn name() -> String {
+        "Finished".into()
+    }
 }
 
 impl marker::TopLevel for Finished {}
@@ -67,6 +79,10 @@ where
     A: marker::Actionable + State,
 {
     type Transaction = <A as State>::Transaction;
+
+    fn name() -> String {
+        format!("Effect<{}>", A::name())
+    }
 }
 
 impl<A> marker::TopLevel for Effect<A>
@@ -83,6 +99,10 @@ where
     TR: marker::Triggerable + State,
 {
     type Transaction = <TR as State>::Transaction;
+
+    fn name() -> String {
+        format!("RecurseEffect<{}>", TR::name())
+    }
 }
 
 impl<TR> EffectState for RecurseEffect<TR>
@@ -100,6 +120,9 @@ where
 #[derive(Debug, Clone)]
 /// Specific state where [`Entity`] death processing is triggered.
 ///
+/// Triggers are executed within this state like within [`Trigger`], which allows
+/// triggers to respond specifically to dying entities.
+///
 /// # Note
 /// [`State`] is implemented using the transaction type of [`Trigger`].
 /// This is because we assume [`Timing`] will always have an irrelevant (epsilon) [`Transaction`].
@@ -110,6 +133,10 @@ where
     TR: marker::Triggerable + State,
 {
     type Transaction = <TR as State>::Transaction;
+
+    fn name() -> String {
+        format!("DeathEffect<{}, {}>", TM::name(), TR::name())
+    }
 }
 
 impl<TM, TR> EffectState for DeathEffect<TM, TR>
@@ -126,6 +153,15 @@ where
 {
 }
 
+impl<TM, TR> TriggerState for DeathEffect<TM, TR>
+where
+    TM: marker::Timing + State,
+    TR: marker::Triggerable + State,
+{
+    type Timing = TM;
+    type Trigger = TR;
+}
+
 #[derive(Debug, Clone)]
 /// Exact state used to execute effects.
 ///
@@ -141,6 +177,10 @@ where
     TR: marker::Triggerable + State,
 {
     type Transaction = <TR as State>::Transaction;
+
+    fn name() -> String {
+        format!("Trigger<{}, {}>", TM::name(), TR::name())
+    }
 }
 
 impl<TM, TR> TriggerState for Trigger<TM, TR>
@@ -13,6 +13,10 @@ pub struct Pre();
 impl marker::Timing for Pre {}
 impl State for Pre {
     type Transaction = Epsilon;
+
+    fn name() -> String {
+        "Pre".into()
+    }
 }
 
 /// Type representing a timing relationship. Peri X means during/while X is executed.
@@ -21,6 +25,10 @@ pub struct Peri();
 impl marker::Timing for Peri {}
 impl State for Peri {
     type Transaction = Epsilon;
+
+    fn name() -> String {
+        "Peri".into()
+    }
 }
 
 /// Type representing a timing relationship. Pre X means after X is executed.
@@ -29,6 +37,10 @@ pub struct Post();
 impl marker::Timing for Post {}
 impl State for Post {
     type Transaction = Epsilon;
+
+    fn name() -> String {
+        "Post".into()
+    }
 }
 
 // value_from_type builds an enumeration of all structures defined within this module.
@@ -10,5 +10,6 @@ use marker;
 /// In this design it's intention is to convey that no Transition information is
 /// necessary to transition into a next state.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 pub struct Epsilon;
 impl marker::Transaction for Epsilon {}
@@ -1,14 +1,16 @@
 //! Contains functionality to work with [`Card`]s.
 
+use std::collections::HashSet;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
+use std::mem;
 
-use error::custom_type::{IDCollisionError, MissingCardError};
-use function::{Card, Identifiable};
+use error::custom_type::{CardSetError, IDCollisionError, MissingCardError};
+use function::{Card, CardSet, Identifiable};
 use marker;
 use storage::CardStorage;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Object allowing manipulation of game [`Card`]s.
 pub struct CardService<C>
 where
@@ -18,6 +20,20 @@ where
     C::TriggerEnum: marker::TriggerEnumerator + Debug + Display + Copy,
 {
     storage: CardStorage<C>,
+    sets: Vec<CardSetInfo<C::ID>>,
+}
+
+#[derive(Debug, Clone)]
+/// Description of a [`CardSet`] installed into a [`CardService`].
+pub struct CardSetInfo<ID> {
+    /// Identifier of the set.
+    pub id: u32,
+    /// Human readable name of the set.
+    pub name: &'static str,
+    /// Version of the set at the moment it was installed.
+    pub version: u32,
+    /// Identifiers of all cards within the set, in the order they were provided.
+    pub cards: Vec<ID>,
 }
 
 impl<C> marker::Service for CardService<C>
@@ -40,6 +56,7 @@ where
     pub fn new() -> Self {
         Self {
             storage: CardStorage::new(),
+            sets: vec![],
         }
     }
 
@@ -48,6 +65,17 @@ where
         self.storage.try_insert_card(card)
     }
 
+    /// Replaces the registered card which has the same identifier as the provided card.
+    ///
+    /// The replaced card is returned.
+    pub fn replace_card(&mut self, card: C) -> Result<C, MissingCardError<C::ID>> {
+        let id = card.id();
+        match self.storage.cards.get_mut(&id) {
+            Some(registered) => Ok(mem::replace(registered, card)),
+            None => Err(MissingCardError(id)),
+        }
+    }
+
     /// Fetches the card matching the provided identifier from this storage object.
     pub fn get_card(&self, id: C::ID) -> Result<&C, MissingCardError<C::ID>> {
         self.storage
@@ -55,4 +83,65 @@ where
             .get(&id)
             .ok_or_else(|| MissingCardError(id))
     }
+
+    /// Registers all cards of the provided set.
+    ///
+    /// Nothing is registered when the set is already installed, or when any of it's
+    /// cards collides with a registered card.
+    pub fn install_set<S>(&mut self, set: &S) -> Result<(), CardSetError<C::ID>>
+    where
+        S: CardSet<Card = C> + ?Sized,
+    {
+        if self.set(set.set_id()).is_some() {
+            return Err(CardSetError::DuplicateSet(set.set_id()));
+        }
+
+        let cards = set.cards();
+        let mut ids = HashSet::new();
+        for card in cards.iter() {
+            let id = card.id();
+            if self.storage.cards.contains_key(&id) || !ids.insert(id) {
+                return Err(CardSetError::DuplicateCard(id));
+            }
+        }
+
+        let info = CardSetInfo {
+            id: set.set_id(),
+            name: set.name(),
+            version: set.version(),
+            cards: cards.iter().map(|c| c.id()).collect(),
+        };
+        for card in cards {
+            // Collisions were verified before registering any card.
+            let _ = self.storage.try_insert_card(card);
+        }
+        self.sets.push(info);
+        Ok(())
+    }
+
+    /// Returns an iterator over all installed sets, in order of installation.
+    pub fn sets(&self) -> impl Iterator<Item = &CardSetInfo<C::ID>> {
+        self.sets.iter()
+    }
+
+    /// Returns the installed set matching the provided identifier.
+    pub fn set(&self, id: u32) -> Option<&CardSetInfo<C::ID>> {
+        self.sets.iter().find(|s| s.id == id)
+    }
+
+    /// Returns an iterator over all registered cards, in arbitrary order.
+    pub fn cards(&self) -> impl Iterator<Item = &C> {
+        self.storage.cards.values()
+    }
+
+    /// Returns an iterator over all cards of the requested set, in the order
+    /// they were provided by the set.
+    ///
+    /// The iterator is empty when the set is not installed.
+    pub fn cards_in_set<'a>(&'a self, id: u32) -> impl Iterator<Item = &'a C> + 'a {
+        let storage = &self.storage;
+        self.set(id)
+            .into_iter()
+            .flat_map(move |s| s.cards.iter().filter_map(move |c| storage.cards.get(c)))
+    }
 }
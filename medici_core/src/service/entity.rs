@@ -1,13 +1,18 @@
 //! Contains functionality to work with [`Entity`]s.
 
 use std::fmt::{Debug, Display};
+use std::hash::Hash;
 
-use error::custom_type::{MissingEntityError, OverflowError};
-use function::{ArrayStorageCompliance, Entity, EntityBuilder};
+use value_from_type_traits::IntoEnum;
+
+use error::custom_type::{EntityAccessError, MissingEntityError, OverflowError};
+use function::{Entity, EntityBuilder, EntityId, Identifiable};
 use marker;
+use prefab::entity::EntityStruct;
 use storage::EntityStorage;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Structure for working with [`Entity`] objects.
 pub struct EntityService<E>
 where
@@ -40,34 +45,214 @@ where
 
     /// Build a new entity which is kept inside this storage object.
     ///
+    /// Slots of removed entities are reused, the new entity receives an id which
+    /// differs from the id of the removed entity.
+    ///
     /// # Returns
     /// A mutable reference to the new entity is returned upon successful creation.
     pub fn new_entity(&mut self) -> Result<&mut E, OverflowError> {
-        let next_eid = self.storage.as_slice().len();
-        if next_eid >= self.maximum_items {
+        if self.storage.len() >= self.maximum_items {
             return Err(OverflowError(self.maximum_items));
         }
-
-        let new_entity = E::new_with_id(next_eid.into());
-        self.storage.push(new_entity);
-        Ok(self.storage.as_slice_mut().last_mut().unwrap())
+        self.storage.push(|id| E::new_with_id(id.into()))
     }
 
     /// Retrieves a reference to the entity matching the id.
+    ///
+    /// Ids of removed entities result in [`MissingEntityError`].
     pub fn get(&self, id: E::ID) -> Result<&E, MissingEntityError<E::ID>> {
-        let idx_id = id.into();
-        self.storage
-            .as_slice()
-            .get(idx_id)
-            .ok_or(MissingEntityError(id))
+        self.storage.get(id.into()).ok_or(MissingEntityError(id))
     }
 
     /// Retrieves a mutable reference to the entity matching the id.
+    ///
+    /// Ids of removed entities result in [`MissingEntityError`].
     pub fn get_mut(&mut self, id: E::ID) -> Result<&mut E, MissingEntityError<E::ID>> {
-        let idx_id = id.into();
         self.storage
-            .as_slice_mut()
-            .get_mut(idx_id)
+            .get_mut(id.into())
             .ok_or(MissingEntityError(id))
     }
+
+    /// Retrieves mutable references to all entities matching the ids, in the same
+    /// order as the ids.
+    ///
+    /// All requested entities are borrowed at the same time, so the ids MUST be unique.
+    pub fn get_many_mut(&mut self, ids: &[E::ID]) -> Result<Vec<&mut E>, EntityAccessError<E::ID>> {
+        let idx_ids: Vec<usize> = ids.iter().map(|&id| id.into()).collect();
+        self.storage.get_many_mut(&idx_ids).map_err(|e| match e {
+            EntityAccessError::Missing(id) => EntityAccessError::Missing(id.into()),
+            EntityAccessError::Duplicate(id) => EntityAccessError::Duplicate(id.into()),
+            EntityAccessError::MissingPrototype(id) => {
+                EntityAccessError::MissingPrototype(id.into())
+            }
+        })
+    }
+
+    /// Retrieves mutable references to both entities matching the ids.
+    pub fn get_pair_mut(
+        &mut self,
+        first: E::ID,
+        second: E::ID,
+    ) -> Result<(&mut E, &mut E), EntityAccessError<E::ID>> {
+        let mut entities = self.get_many_mut(&[first, second])?;
+        let second = entities.pop().unwrap();
+        let first = entities.pop().unwrap();
+        Ok((first, second))
+    }
+
+    /// Returns the amount of entities within this storage object.
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns true if this storage object holds no entities.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    /// Returns an iterator over all entities within this storage object.
+    ///
+    /// Removed entities are skipped.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.storage.iter()
+    }
+
+    /// Returns an iterator over the ids of all entities within this storage object.
+    pub fn ids<'a>(&'a self) -> impl Iterator<Item = E::ID> + 'a {
+        self.storage.iter().map(Identifiable::id)
+    }
+
+    /// Returns an iterator over mutable references to all entities within this
+    /// storage object.
+    ///
+    /// Removed entities are skipped.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut E> {
+        self.storage.iter_mut()
+    }
+
+    /// Removes the entity matching the id from this storage object.
+    ///
+    /// The id of the removed entity is never handed out again.
+    ///
+    /// # Returns
+    /// The removed entity is returned upon success.
+    pub fn remove_entity(&mut self, id: E::ID) -> Result<E, MissingEntityError<E::ID>> {
+        self.storage.remove(id.into()).ok_or(MissingEntityError(id))
+    }
+}
+
+/// Queries over the properties and prototypes of [`EntityStruct`] objects.
+///
+/// Immutable queries return references to the matching entities, the ids can
+/// be retrieved through [`Identifiable::id`].
+impl<S, P> EntityService<EntityStruct<S, P>>
+where
+    S: Debug + Clone + Eq + Hash,
+    P: marker::ProtoEnumerator + Debug + Clone + Eq + Hash,
+{
+    /// Returns an iterator over all entities which have the requested prototype attached.
+    pub fn with_proto<'a, PT>(&'a self) -> impl Iterator<Item = &'a EntityStruct<S, P>> + 'a
+    where
+        PT: marker::Prototype + IntoEnum<P> + 'a,
+    {
+        let proto_item: P = PT::into_enum();
+        self.iter()
+            .filter(move |e| e.prototypes.contains(&proto_item))
+    }
+
+    /// Returns the first entity which has the requested prototype attached.
+    pub fn first_with_proto<'a, PT>(&'a self) -> Option<&'a EntityStruct<S, P>>
+    where
+        PT: marker::Prototype + IntoEnum<P> + 'a,
+    {
+        self.with_proto::<PT>().next()
+    }
+
+    /// Returns an iterator over all entities which hold the requested property
+    /// and which value matches the provided predicate.
+    pub fn where_tag<'a, F>(
+        &'a self,
+        key: S,
+        predicate: F,
+    ) -> impl Iterator<Item = &'a EntityStruct<S, P>> + 'a
+    where
+        F: Fn(u32) -> bool + 'a,
+    {
+        self.iter()
+            .filter(move |e| e.state.get(&key).map_or(false, |v| predicate(*v)))
+    }
+
+    /// Returns an iterator over all entities, wrapped into the requested prototype,
+    /// which have that prototype attached.
+    pub fn with_proto_mut<'a, PT>(&'a mut self) -> impl Iterator<Item = PT> + 'a
+    where
+        PT: marker::Prototype + IntoEnum<P> + From<&'a mut EntityStruct<S, P>> + 'a,
+    {
+        self.iter_mut().filter_map(|e| e.as_proto_mut::<PT>().ok())
+    }
+
+    /// Returns the first entity, wrapped into the requested prototype, which has
+    /// that prototype attached.
+    pub fn first_with_proto_mut<'a, PT>(&'a mut self) -> Option<PT>
+    where
+        PT: marker::Prototype + IntoEnum<P> + From<&'a mut EntityStruct<S, P>> + 'a,
+    {
+        self.with_proto_mut::<PT>().next()
+    }
+
+    /// Retrieves all entities matching the ids, wrapped into the requested prototype.
+    ///
+    /// See [`EntityService::get_many_mut`].
+    pub fn get_many_proto_mut<'a, PT>(
+        &'a mut self,
+        ids: &[EntityId],
+    ) -> Result<Vec<PT>, EntityAccessError<EntityId>>
+    where
+        PT: marker::Prototype + IntoEnum<P> + From<&'a mut EntityStruct<S, P>>,
+    {
+        self.get_many_mut(ids)?
+            .into_iter()
+            .map(|e| {
+                let id = e.id();
+                e.as_proto_mut::<PT>()
+                    .map_err(|_| EntityAccessError::MissingPrototype(id))
+            })
+            .collect()
+    }
+
+    /// Retrieves both entities matching the ids, wrapped into the requested prototypes.
+    ///
+    /// See [`EntityService::get_pair_mut`].
+    pub fn get_pair_proto_mut<'a, PA, PB>(
+        &'a mut self,
+        first: EntityId,
+        second: EntityId,
+    ) -> Result<(PA, PB), EntityAccessError<EntityId>>
+    where
+        PA: marker::Prototype + IntoEnum<P> + From<&'a mut EntityStruct<S, P>>,
+        PB: marker::Prototype + IntoEnum<P> + From<&'a mut EntityStruct<S, P>>,
+    {
+        let (a, b) = self.get_pair_mut(first, second)?;
+        let a = a
+            .as_proto_mut::<PA>()
+            .map_err(|_| EntityAccessError::MissingPrototype(first))?;
+        let b = b
+            .as_proto_mut::<PB>()
+            .map_err(|_| EntityAccessError::MissingPrototype(second))?;
+        Ok((a, b))
+    }
+
+    /// Returns an iterator over mutable references to all entities which hold the
+    /// requested property and which value matches the provided predicate.
+    pub fn where_tag_mut<'a, F>(
+        &'a mut self,
+        key: S,
+        predicate: F,
+    ) -> impl Iterator<Item = &'a mut EntityStruct<S, P>> + 'a
+    where
+        F: Fn(u32) -> bool + 'a,
+    {
+        self.iter_mut()
+            .filter(move |e| e.state.get(&key).map_or(false, |v| predicate(*v)))
+    }
 }
@@ -6,8 +6,13 @@
 
 pub mod card;
 pub mod entity;
+pub mod rng;
 pub mod trigger;
+pub mod zone;
 
-pub use self::card::CardService;
+pub use self::card::{CardService, CardSetInfo};
 pub use self::entity::EntityService;
-pub use self::trigger::TriggerService;
+pub use self::rng::RngService;
+pub use self::trigger::{current_player_first, default_trigger_order, TriggerComparator,
+                        TriggerOptions, TriggerOrderContext, TriggerRegistry, TriggerService};
+pub use self::zone::ZoneService;
@@ -0,0 +1,95 @@
+//! Contains functionality to generate random numbers.
+
+use marker;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Structure generating pseudo random numbers for the state machine.
+///
+/// The generator is a xoshiro256** generator which state is derived from an explicit
+/// seed. The state is part of the service, so cloning the machine also clones
+/// the sequence of numbers that will be generated. The same seed will always produce
+/// the same sequence of numbers.
+pub struct RngService {
+    seed: u64,
+    state: [u64; 4],
+}
+
+impl marker::Service for RngService {}
+
+impl RngService {
+    /// Creates a new object of this service, generating numbers from the provided seed.
+    pub fn new(seed: u64) -> Self {
+        // SplitMix64 expands the seed into the full state, which must never be all zero.
+        let mut x = seed;
+        let mut state = [0; 4];
+        for s in state.iter_mut() {
+            x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            *s = z ^ (z >> 31);
+        }
+        Self { seed, state }
+    }
+
+    /// Returns the seed this service was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the next pseudo random number.
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    /// Returns a number within the range [low, high).
+    ///
+    /// Each number of the range is equally likely to be returned.
+    ///
+    /// # Panics
+    /// Panics when the range is empty, eg `low >= high`.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        assert!(low < high, "Empty range [{:}, {:})", low, high);
+        let span = (high - low) as u64;
+        // Numbers above the last multiple of span are rejected so no number of the
+        // range is favoured.
+        let limit = u64::max_value() - u64::max_value() % span;
+        loop {
+            let value = self.next_u64();
+            if value < limit {
+                return low + (value % span) as usize;
+            }
+        }
+    }
+
+    /// Returns a random item of the provided slice.
+    ///
+    /// [`None`] is returned when the slice is empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        let idx = self.range(0, items.len());
+        items.get(idx)
+    }
+
+    /// Randomly reorders the items of the provided slice.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        // Fisher-Yates shuffle.
+        for idx in (1..items.len()).rev() {
+            let other = self.range(0, idx + 1);
+            items.swap(idx, other);
+        }
+    }
+}
@@ -1,15 +1,19 @@
 //! Contains the functionality to work with [`Trigger`]s.
 
-use std::cmp::PartialEq;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::cmp::{Ordering, PartialEq};
+use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
 use value_from_type_traits::IntoEnum;
 
-use error::custom_type::TriggerFail;
+use error::custom_type::{IDCollisionError, MissingTriggerError, RecursionLimitError, TriggerFail};
 use error::MachineError;
-use function::{StateContainer, TriggerState};
+use function::{EntityId, StateContainer, TriggerState};
 use marker;
-use storage::trigger::{TriggerStorage, UnsafeTrigger};
+use storage::trigger::{ErasedClosure, TriggerHandle, TriggerStorage, UnsafeTrigger};
 
 // Shortcut for a callback method prototype which consumes the machine
 // and returns it again.. or a generic error.
@@ -17,6 +21,111 @@ use storage::trigger::{TriggerStorage, UnsafeTrigger};
 // TODO; Transfrom Error into a real error type.
 pub(crate) type _FNTrigger<M> = fn(M) -> Result<M, MachineError>;
 
+// Shortcut for a condition method prototype which inspects the machine
+// and decides if the trigger must be executed.
+pub(crate) type _FNCondition<M> = fn(&M) -> bool;
+
+// Shortcut for a callback closure which consumes the machine and returns
+// it again.. or a generic error.
+// Closures can capture data, so one implementation can be shared by multiple
+// triggers.
+pub(crate) type _ClosureTrigger<M> = Arc<Fn(M) -> Result<M, MachineError> + Send + Sync>;
+
+/// The callback of a trigger.
+#[derive(Clone)]
+pub enum TriggerCallback<M> {
+    /// Callback as plain method.
+    Pointer(_FNTrigger<M>),
+    /// Callback as closure, which can capture data.
+    Closure(_ClosureTrigger<M>),
+}
+
+impl<M> TriggerCallback<M> {
+    /// Executes the callback on the provided machine.
+    pub fn call(&self, machine: M) -> Result<M, MachineError> {
+        match *self {
+            TriggerCallback::Pointer(cb) => cb(machine),
+            TriggerCallback::Closure(ref cb) => cb(machine),
+        }
+    }
+}
+
+impl<M> fmt::Debug for TriggerCallback<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TriggerCallback::Pointer(cb) => write!(f, "Pointer({:p})", cb as *const ()),
+            TriggerCallback::Closure(ref cb) => write!(f, "Closure({:p})", &**cb),
+        }
+    }
+}
+
+/// Default maximum amount of nested trigger chains, see [`TriggerService::set_recursion_limit`].
+pub const DEFAULT_RECURSION_LIMIT: usize = 32;
+
+/// Information about the running machine which is made available to a
+/// [`TriggerComparator`].
+///
+/// State machines which execute triggers implement this trait, so triggers can
+/// be ordered relative to the game itself.
+pub trait TriggerOrderContext {
+    /// Returns the player which currently has the turn, if any.
+    fn current_player(&self) -> Option<EntityId>;
+
+    /// Returns the player controlling the provided entity, if any.
+    fn controller_of(&self, entity: EntityId) -> Option<EntityId>;
+}
+
+/// Comparator defining the execution order of triggers, see [`TriggerService::set_comparator`].
+///
+/// The first argument is the machine for which the triggers are retrieved.
+pub type TriggerComparator<ETM, ETR> =
+    fn(&TriggerOrderContext, &UnsafeTrigger<ETM, ETR>, &UnsafeTrigger<ETM, ETR>) -> Ordering;
+
+/// The default execution order of triggers.
+///
+/// Triggers are ordered on descending priority, then ascending ordering key and
+/// finally in order of registration.
+pub fn default_trigger_order<ETM, ETR>(
+    _: &TriggerOrderContext,
+    a: &UnsafeTrigger<ETM, ETR>,
+    b: &UnsafeTrigger<ETM, ETR>,
+) -> Ordering
+where
+    ETM: marker::TimingEnumerator + Copy,
+    ETR: marker::TriggerEnumerator + Copy,
+{
+    b.priority
+        .cmp(&a.priority)
+        .then(a.order.cmp(&b.order))
+        .then(a.handle.cmp(&b.handle))
+}
+
+/// Execution order which resolves triggers controlled by the current player first.
+///
+/// A trigger is controlled by the current player when its owner is that player, or
+/// when the owner is controlled by that player. Triggers of equal standing are
+/// ordered by [`default_trigger_order`].
+pub fn current_player_first<ETM, ETR>(
+    context: &TriggerOrderContext,
+    a: &UnsafeTrigger<ETM, ETR>,
+    b: &UnsafeTrigger<ETM, ETR>,
+) -> Ordering
+where
+    ETM: marker::TimingEnumerator + Copy,
+    ETR: marker::TriggerEnumerator + Copy,
+{
+    let current = context.current_player();
+    let is_current = |t: &UnsafeTrigger<ETM, ETR>| match (t.owner, current) {
+        (Some(owner), Some(player)) => {
+            owner == player || context.controller_of(owner) == Some(player)
+        }
+        _ => false,
+    };
+    is_current(b)
+        .cmp(&is_current(a))
+        .then_with(|| default_trigger_order(context, a, b))
+}
+
 /// Safe abstraction over UnsafeTrigger objects.
 #[derive(Debug)]
 pub struct TriggerWrapper<M, ETM, ETR>
@@ -30,7 +139,8 @@ where
     // Additional constraint inference -> *const (): Send + Sync
     _FNTrigger<M>: Send + Sync,
 {
-    cb: _FNTrigger<M>,
+    cb: TriggerCallback<M>,
+    condition: Option<_FNCondition<M>>,
     phantom: PhantomData<(ETM, ETR)>,
 }
 
@@ -46,7 +156,27 @@ where
     /// Constructs a new trigger method wrapper from the provided method.
     pub fn new(cb: _FNTrigger<M>) -> Self {
         Self {
-            cb,
+            cb: TriggerCallback::Pointer(cb),
+            condition: None,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new trigger method wrapper from the provided method, which is only
+    /// executed when the provided condition holds.
+    pub fn new_conditional(cb: _FNTrigger<M>, condition: _FNCondition<M>) -> Self {
+        Self {
+            cb: TriggerCallback::Pointer(cb),
+            condition: Some(condition),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Constructs a new trigger wrapper from the provided closure.
+    pub fn new_closure(cb: _ClosureTrigger<M>) -> Self {
+        Self {
+            cb: TriggerCallback::Closure(cb),
+            condition: None,
             phantom: PhantomData,
         }
     }
@@ -55,19 +185,33 @@ where
     ///
     /// # Safety
     /// The exact [`StateContainer`] (state machine) is removed from the [`UnsafeTrigger`].
-    /// A safe wrapper can thus be generated for ANY state machine which [`TriggerState`]-associated
-    /// types Timing and Trigger match on the requested machine.
+    /// Only the family of the machine is kept, see [`StateContainer::Family`].
+    /// A safe wrapper can thus be generated for ANY state of that machine which
+    /// [`TriggerState`]-associated types Timing and Trigger match on the requested machine.
+    ///
+    /// # Errors
+    /// [`TriggerFail::MachineMismatch`] is returned when the trigger was built for
+    /// another state machine.
+    /// [`TriggerFail::CallbackMismatch`] is returned when the closure of the trigger
+    /// was built for another machine type.
     ///
     /// See [`TriggerService`] for more information!
-    pub unsafe fn try_from_trigger_entry(x: UnsafeTrigger<ETM, ETR>) -> Result<Self, TriggerFail> {
+    pub unsafe fn try_from_trigger_entry(x: UnsafeTrigger<ETM, ETR>) -> Result<Self, TriggerFail>
+    where
+        M: 'static,
+    {
         let timing_key: ETM = <M::State as TriggerState>::Timing::into_enum();
         let trigger_key: ETR = <M::State as TriggerState>::Trigger::into_enum();
 
-        if x.func_pointer.is_null() {
+        if x.func_pointer.is_null() && x.closure.is_none() {
             // TODO: Transform into real error!
             return Err(TriggerFail::CallbackNull);
         }
 
+        if x.machine_family != TypeId::of::<M::Family>() {
+            return Err(TriggerFail::MachineMismatch);
+        }
+
         if x.timing != timing_key || x.trigger != trigger_key {
             // TODO; Transform into real error!
             return Err(TriggerFail::ConstraintFail);
@@ -77,26 +221,65 @@ where
         // compatible with the current Timing and Trigger.
         //
         // Note(UB):
-        // This uses the provided State-Machine [M] as conversion template
-        // but there is nothing enforcing usage of the CORRECT machine!
+        // This uses the provided State-Machine [M] as conversion template.
+        // The family check above guarantees the callback was built for a state of the
+        // same machine, and the Timing and Trigger check guarantees it was built for the
+        // current state. The transition history of the machine is NOT verified, callbacks
+        // are expected to be generic over it!
         unsafe {
-            let transmuted: _FNTrigger<M> = ::std::mem::transmute(x.func_pointer);
+            let cb = match x.closure {
+                // Closures are stored for the normalized machine, so their exact type
+                // is verified.
+                Some(ref erased) => {
+                    let erased: &Any = &*erased.closure;
+                    let closure = match erased.downcast_ref::<_ClosureTrigger<M::Normalized>>() {
+                        Some(closure) => closure.clone(),
+                        None => return Err(TriggerFail::CallbackMismatch),
+                    };
+                    let cb: _ClosureTrigger<M> = Arc::new(move |machine: M| {
+                        closure(machine.into_normalized()).map(M::from_normalized)
+                    });
+                    TriggerCallback::Closure(cb)
+                }
+                None => TriggerCallback::Pointer(::std::mem::transmute(x.func_pointer)),
+            };
+            let condition: Option<_FNCondition<M>> =
+                x.condition_pointer.map(|c| ::std::mem::transmute(c));
             Ok(Self {
-                cb: transmuted,
+                cb,
+                condition,
                 phantom: PhantomData,
             })
         }
     }
 
+    /// Tests the condition of the provided [`UnsafeTrigger`] against the provided machine.
+    ///
+    /// Triggers without condition always hold. Triggers which cannot be wrapped for the
+    /// provided machine also hold, so the failure is reported when executing them,
+    /// see [`exec_trigger_stepped`].
+    ///
+    /// # Safety
+    /// Same constraints as [`TriggerWrapper::try_from_trigger_entry`] apply.
+    pub unsafe fn condition_holds(x: &UnsafeTrigger<ETM, ETR>, machine: &M) -> bool
+    where
+        M: 'static,
+    {
+        match Self::try_from_trigger_entry(x.clone()) {
+            Ok(wrapper) => wrapper.condition.map_or(true, |c| c(machine)),
+            Err(_) => true,
+        }
+    }
+
     /// Consumes this wrapper to retrieve the callback it contains.
-    pub fn into_callback(self) -> _FNTrigger<M> {
+    pub fn into_callback(self) -> TriggerCallback<M> {
         self.cb
     }
 }
 
 impl<M, ETM, ETR> From<TriggerWrapper<M, ETM, ETR>> for UnsafeTrigger<ETM, ETR>
 where
-    M: StateContainer,
+    M: StateContainer + 'static,
     M::State: TriggerState,
     <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
     <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
@@ -106,13 +289,35 @@ where
     fn from(x: TriggerWrapper<M, ETM, ETR>) -> Self {
         let timing_key: ETM = <M::State as TriggerState>::Timing::into_enum();
         let trigger_key: ETR = <M::State as TriggerState>::Trigger::into_enum();
+        // Transformation is performed here to make the handler generic
+        // for storage.
+        let (func_pointer, closure) = match x.cb {
+            TriggerCallback::Pointer(cb) => (cb as *const (), None),
+            TriggerCallback::Closure(cb) => {
+                let key = &*cb as *const _ as *const () as usize;
+                // The closure is stored for the normalized machine, so it can be verified
+                // when unpacking, see [`TriggerWrapper::try_from_trigger_entry`].
+                let normalized: _ClosureTrigger<M::Normalized> = Arc::new(move |machine| {
+                    cb(M::from_normalized(machine)).map(M::into_normalized)
+                });
+                let closure: Arc<Any + Send + Sync> = Arc::new(normalized);
+                (::std::ptr::null(), Some(ErasedClosure { closure, key }))
+            }
+        };
 
         Self {
+            // The handle is assigned when the trigger is stored.
+            handle: TriggerHandle::default(),
+            name: None,
+            owner: None,
+            priority: 0,
+            order: 0,
+            machine_family: TypeId::of::<M::Family>(),
             timing: timing_key,
             trigger: trigger_key,
-            // Transformation is performed here to make the handler generic
-            // for storage.
-            func_pointer: x.cb as *const (),
+            func_pointer,
+            closure,
+            condition_pointer: x.condition.map(|c| c as *const ()),
             //
             _private: PhantomData,
         }
@@ -129,9 +334,9 @@ where
 ///
 /// TODO: Think about enforcing this check at compile time.
 ///
-/// Note: This could be enforced by letting the StateContainer return a unique
-/// number for each created object. This number could be used as constraint
-/// when reconstructing [`TriggerWrapper`].
+/// Note: This is verified at runtime by storing the [`StateContainer::Family`]
+/// of the machine within each trigger, reconstructing a [`TriggerWrapper`] for
+/// another machine fails with [`TriggerFail::MachineMismatch`].
 ///
 /// Note: The trigger methods could be written as if they respond to a certain [`Timing`]
 /// and [`Trigger`] where the exact machine format is abstracted.
@@ -155,13 +360,33 @@ where
 ///     unimplemented!()
 /// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct TriggerService<ETM, ETR>
 where
     ETM: marker::TimingEnumerator + PartialEq + Copy,
     ETR: marker::TriggerEnumerator + PartialEq + Copy,
 {
     storage: TriggerStorage<ETM, ETR>,
+    comparator: TriggerComparator<ETM, ETR>,
+    recursion_limit: usize,
+    recursion_depth: usize,
+    active: Vec<Cow<'static, str>>,
+}
+
+impl<ETM, ETR> fmt::Debug for TriggerService<ETM, ETR>
+where
+    ETM: marker::TimingEnumerator + PartialEq + Copy + fmt::Debug,
+    ETR: marker::TriggerEnumerator + PartialEq + Copy + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TriggerService")
+            .field("storage", &self.storage)
+            .field("comparator", &(self.comparator as *const ()))
+            .field("recursion_limit", &self.recursion_limit)
+            .field("recursion_depth", &self.recursion_depth)
+            .field("active", &self.active)
+            .finish()
+    }
 }
 
 impl<ETM, ETR> marker::Service for TriggerService<ETM, ETR>
@@ -180,29 +405,237 @@ where
     pub fn new() -> Self {
         Self {
             storage: TriggerStorage::new(),
+            comparator: default_trigger_order,
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+            recursion_depth: 0,
+            active: vec![],
         }
     }
 
+    /// Sets the maximum amount of nested trigger chains, see [`build_exec_recurse_checked`].
+    pub fn set_recursion_limit(&mut self, limit: usize) {
+        self.recursion_limit = limit;
+    }
+
+    /// Returns the maximum amount of nested trigger chains.
+    pub fn recursion_limit(&self) -> usize {
+        self.recursion_limit
+    }
+
+    /// Returns the amount of trigger chains currently nested.
+    pub fn recursion_depth(&self) -> usize {
+        self.recursion_depth
+    }
+
+    /// Returns the names of all triggers currently executing, the outermost
+    /// trigger is returned first.
+    ///
+    /// Unnamed triggers are represented by their handle.
+    pub fn active_chain(&self) -> Vec<String> {
+        self.active.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// Registers the start of a new nested trigger chain.
+    ///
+    /// # Errors
+    /// [`RecursionLimitError`] is returned, without registering the chain, when
+    /// the recursion limit is reached.
+    pub fn enter_recursion(&mut self) -> Result<(), RecursionLimitError> {
+        if self.recursion_depth >= self.recursion_limit {
+            return Err(RecursionLimitError(self.recursion_limit));
+        }
+        self.recursion_depth += 1;
+        Ok(())
+    }
+
+    /// Registers the end of the innermost nested trigger chain.
+    pub fn exit_recursion(&mut self) {
+        self.recursion_depth = self.recursion_depth.saturating_sub(1);
+    }
+
+    /// Registers the start of execution of the provided trigger.
+    pub fn enter_trigger(&mut self, trigger: &UnsafeTrigger<ETM, ETR>) {
+        let name = match trigger.name {
+            Some(ref name) => name.clone(),
+            None => trigger.handle.to_string().into(),
+        };
+        self.active.push(name);
+    }
+
+    /// Registers the end of execution of the innermost trigger.
+    pub fn exit_trigger(&mut self) {
+        self.active.pop();
+    }
+
+    /// Replaces the comparator which defines the execution order of triggers.
+    ///
+    /// The comparator MUST define a total order, otherwise the resolution order of
+    /// triggers is not reproducible. See [`default_trigger_order`] for the default.
+    pub fn set_comparator(&mut self, comparator: TriggerComparator<ETM, ETR>) {
+        self.comparator = comparator;
+    }
+
     /// Add a new trigger to the store.
-    pub fn add_trigger<M>(&mut self, cb: _FNTrigger<M>)
+    ///
+    /// The returned handle can be used to remove the trigger again.
+    pub fn add_trigger<M>(&mut self, cb: _FNTrigger<M>) -> TriggerHandle
     where
-        M: StateContainer,
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        self.add_trigger_with(cb, TriggerOptions::default())
+    }
+
+    /// Add a new trigger, with the provided metadata, to the store.
+    pub fn add_trigger_with<M>(
+        &mut self,
+        cb: _FNTrigger<M>,
+        options: TriggerOptions,
+    ) -> TriggerHandle
+    where
+        M: StateContainer + 'static,
         M::State: TriggerState,
         <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
         <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
     {
         // Both the new method AND the Into trait will do the hard work for us!
         let safe_wrapper = TriggerWrapper::<M, ETM, ETR>::new(cb);
-        self.storage.triggers.push(safe_wrapper.into());
+        self.store_with(safe_wrapper.into(), options)
+    }
+
+    /// Add a new trigger, with the provided metadata, to the store.
+    ///
+    /// The trigger is only executed when the provided condition holds for the machine
+    /// at the moment triggers are fetched, see [`fetch_triggers`].
+    pub fn add_conditional_trigger<M>(
+        &mut self,
+        cb: _FNTrigger<M>,
+        condition: _FNCondition<M>,
+        options: TriggerOptions,
+    ) -> TriggerHandle
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        let safe_wrapper = TriggerWrapper::<M, ETM, ETR>::new_conditional(cb, condition);
+        self.store_with(safe_wrapper.into(), options)
+    }
+
+    /// Add a new closure trigger, with the provided metadata, to the store.
+    ///
+    /// The closure can capture data, so one implementation can be shared between
+    /// multiple triggers. Closures are matched on Timing and Trigger exactly like
+    /// plain methods.
+    pub fn add_closure_trigger<M>(
+        &mut self,
+        cb: _ClosureTrigger<M>,
+        options: TriggerOptions,
+    ) -> TriggerHandle
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        let safe_wrapper = TriggerWrapper::<M, ETM, ETR>::new_closure(cb);
+        self.store_with(safe_wrapper.into(), options)
+    }
+
+    /// Add a new trigger, which callback is registered within the provided registry
+    /// under the provided name, to the store.
+    pub fn add_registered_trigger(
+        &mut self,
+        registry: &TriggerRegistry<ETM, ETR>,
+        callback: &str,
+        options: TriggerOptions,
+    ) -> Result<TriggerHandle, MissingTriggerError<String>> {
+        let trigger = registry
+            .get(callback)
+            .cloned()
+            .ok_or_else(|| MissingTriggerError(callback.to_string()))?;
+        Ok(self.store_with(trigger, options))
+    }
+
+    fn store_with(
+        &mut self,
+        mut trigger: UnsafeTrigger<ETM, ETR>,
+        options: TriggerOptions,
+    ) -> TriggerHandle {
+        trigger.name = options.name;
+        trigger.owner = options.owner;
+        trigger.priority = options.priority;
+        trigger.order = options.order;
+        self.storage.push(trigger)
+    }
+
+    /// Removes the trigger matching the provided handle.
+    ///
+    /// The removed trigger is returned if it was found.
+    pub fn remove_trigger(&mut self, handle: TriggerHandle) -> Option<UnsafeTrigger<ETM, ETR>> {
+        let idx = self.storage
+            .triggers
+            .iter()
+            .position(|t| t.handle == handle)?;
+        Some(self.storage.triggers.remove(idx))
+    }
+
+    /// Removes all triggers owned by the provided entity.
+    ///
+    /// The amount of removed triggers is returned.
+    pub fn remove_triggers_owned_by(&mut self, owner: EntityId) -> usize {
+        let old_len = self.storage.triggers.len();
+        self.storage
+            .triggers
+            .retain(|t| t.owner != Some(owner));
+        old_len - self.storage.triggers.len()
+    }
+
+    /// Copies all triggers of the provided service into this service.
+    ///
+    /// Each copied trigger receives a new handle and is owned by the provided entity,
+    /// names are kept. This is used to activate the triggers defined on a card for
+    /// one specific entity.
+    /// The handles of the copied triggers are returned in order of registration.
+    pub fn install_triggers(&mut self, source: &Self, owner: EntityId) -> Vec<TriggerHandle> {
+        source
+            .storage
+            .triggers
+            .iter()
+            .map(|t| {
+                let mut trigger = t.clone();
+                trigger.owner = Some(owner);
+                self.storage.push(trigger)
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over all triggers owned by the provided entity.
+    pub fn retrieve_triggers_owned_by<'a>(
+        &'a self,
+        owner: EntityId,
+    ) -> impl Iterator<Item = &'a UnsafeTrigger<ETM, ETR>> + 'a {
+        self.storage
+            .triggers
+            .iter()
+            .filter(move |t| t.owner == Some(owner))
     }
 
     /// Returns an iterator over all stored triggers.
+    ///
+    /// The metadata of each trigger is accessible through the fields of [`UnsafeTrigger`].
     pub fn retrieve_all_triggers(&self) -> impl Iterator<Item = &UnsafeTrigger<ETM, ETR>> {
         self.storage.triggers.iter()
     }
 
     /// Retrieve all triggers matching the provided machine.
     ///
+    /// The triggers are returned in execution order, as defined by the comparator
+    /// of this service.
+    ///
     /// # Borrow-check
     /// This method might cause issues with the borrow checker because [`Service`] is designed
     /// to be contained by a state machine. By accessing this specific service we place
@@ -217,9 +650,9 @@ where
     /// [`UnsafeTrigger`] reference. Ultimately we leave the choice of usage up to the framework
     /// user. The reason being that we want additional functional operations to be as lightweight
     /// as possible.
-    pub fn retrieve_triggers<M>(&self, _: &M) -> impl Iterator<Item = &UnsafeTrigger<ETM, ETR>>
+    pub fn retrieve_triggers<M>(&self, machine: &M) -> impl Iterator<Item = &UnsafeTrigger<ETM, ETR>>
     where
-        M: StateContainer,
+        M: StateContainer + TriggerOrderContext,
         M::State: TriggerState,
         <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
         <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
@@ -227,11 +660,260 @@ where
         let timing_key: ETM = <M::State as TriggerState>::Timing::into_enum();
         let trigger_key: ETR = <M::State as TriggerState>::Trigger::into_enum();
 
-        self.storage
+        let mut triggers: Vec<_> = self.storage
             .triggers
             .iter()
             .filter(move |e| e.timing == timing_key)
             .filter(move |e| e.trigger == trigger_key)
+            .collect();
+        // Sorting is stable, so the order of equal triggers stays reproducible.
+        let comparator = self.comparator;
+        triggers.sort_by(|a, b| comparator(machine, a, b));
+        triggers.into_iter()
         // Note: We could map a safe wrapper on top of this iterator?
     }
+
+    /// Builds a portable description of all stored triggers.
+    ///
+    /// Each trigger is described by the name it's callback is registered with inside the
+    /// provided registry.
+    ///
+    /// # Errors
+    /// [`TriggerFail::Unregistered`] is returned when the callback of any stored trigger
+    /// is unknown to the registry.
+    pub fn snapshot(
+        &self,
+        registry: &TriggerRegistry<ETM, ETR>,
+    ) -> Result<TriggerSnapshot, TriggerFail> {
+        let triggers = self.storage
+            .triggers
+            .iter()
+            .map(|t| {
+                registry
+                    .name_of(t)
+                    .map(|callback| TriggerEntrySnapshot {
+                        callback: callback.into(),
+                        handle: t.handle,
+                        name: t.name.as_ref().map(|n| n.to_string()),
+                        owner: t.owner,
+                        priority: t.priority,
+                        order: t.order,
+                    })
+                    .ok_or(TriggerFail::Unregistered)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(TriggerSnapshot {
+            triggers,
+            recursion_limit: self.recursion_limit,
+        })
+    }
+
+    /// Rebuilds the service from the provided snapshot, see [`TriggerService::snapshot`].
+    ///
+    /// The callbacks are re-bound through the provided registry.
+    /// The comparator is not part of the snapshot, the restored service uses
+    /// [`default_trigger_order`].
+    pub fn restore(
+        snapshot: &TriggerSnapshot,
+        registry: &TriggerRegistry<ETM, ETR>,
+    ) -> Result<Self, MissingTriggerError<String>> {
+        let mut service = Self::new();
+        service.recursion_limit = snapshot.recursion_limit;
+        for entry in snapshot.triggers.iter() {
+            let mut trigger = registry
+                .get(&entry.callback)
+                .cloned()
+                .ok_or_else(|| MissingTriggerError(entry.callback.clone()))?;
+            // Handles are preserved, so references to the triggers stay valid.
+            trigger.handle = entry.handle;
+            trigger.name = entry.name.clone().map(Cow::Owned);
+            trigger.owner = entry.owner;
+            trigger.priority = entry.priority;
+            trigger.order = entry.order;
+            service.storage.triggers.push(trigger);
+            service.storage.next_handle = service.storage.next_handle.max(entry.handle.0 + 1);
+        }
+        Ok(service)
+    }
+}
+
+/// Portable description of one trigger stored within a [`TriggerService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct TriggerEntrySnapshot {
+    /// Name under which the callback of the trigger is registered, see [`TriggerRegistry`].
+    pub callback: String,
+    /// Handle of the trigger.
+    pub handle: TriggerHandle,
+    /// Human readable name of the trigger.
+    pub name: Option<String>,
+    /// Entity which registered the trigger.
+    pub owner: Option<EntityId>,
+    /// Priority of the trigger.
+    pub priority: i32,
+    /// Ordering key of the trigger.
+    pub order: u64,
+}
+
+/// Metadata attached to a trigger when it's added to a [`TriggerService`].
+#[derive(Debug, Clone, Default)]
+pub struct TriggerOptions {
+    /// Human readable name of the trigger.
+    pub name: Option<Cow<'static, str>>,
+    /// Entity which registers the trigger.
+    ///
+    /// All triggers of an entity can be removed at once, see
+    /// [`TriggerService::remove_triggers_owned_by`].
+    pub owner: Option<EntityId>,
+    /// Priority of the trigger, triggers with a higher priority are executed first.
+    pub priority: i32,
+    /// Ordering key of the trigger, used to order triggers with equal priority.
+    pub order: u64,
+}
+
+/// Portable description of all triggers stored within a [`TriggerService`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct TriggerSnapshot {
+    /// All triggers in order of registration.
+    pub triggers: Vec<TriggerEntrySnapshot>,
+    /// Maximum amount of nested trigger chains, see
+    /// [`TriggerService::set_recursion_limit`].
+    pub recursion_limit: usize,
+}
+
+impl Default for TriggerSnapshot {
+    fn default() -> Self {
+        Self {
+            triggers: vec![],
+            recursion_limit: DEFAULT_RECURSION_LIMIT,
+        }
+    }
+}
+
+/// Object binding trigger callbacks to stable names.
+///
+/// The callback pointer of an [`UnsafeTrigger`] is only valid within the running process.
+/// Triggers are persisted by the name of their callback and re-bound through this registry
+/// when loaded.
+#[derive(Debug, Clone)]
+pub struct TriggerRegistry<ETM, ETR>
+where
+    ETM: marker::TimingEnumerator + PartialEq + Copy,
+    ETR: marker::TriggerEnumerator + PartialEq + Copy,
+{
+    entries: Vec<(&'static str, UnsafeTrigger<ETM, ETR>)>,
+}
+
+impl<ETM, ETR> TriggerRegistry<ETM, ETR>
+where
+    ETM: marker::TimingEnumerator + PartialEq + Copy,
+    ETR: marker::TriggerEnumerator + PartialEq + Copy,
+{
+    /// Creates a new, empty, registry.
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Registers the provided callback under the provided name.
+    ///
+    /// The name MUST be unique within this registry.
+    pub fn register<M>(
+        &mut self,
+        name: &'static str,
+        cb: _FNTrigger<M>,
+    ) -> Result<(), IDCollisionError<&'static str>>
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        self.register_wrapper(name, TriggerWrapper::<M, ETM, ETR>::new(cb))
+    }
+
+    /// Registers the provided callback, together with it's condition, under the
+    /// provided name.
+    ///
+    /// The name MUST be unique within this registry.
+    pub fn register_conditional<M>(
+        &mut self,
+        name: &'static str,
+        cb: _FNTrigger<M>,
+        condition: _FNCondition<M>,
+    ) -> Result<(), IDCollisionError<&'static str>>
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        self.register_wrapper(
+            name,
+            TriggerWrapper::<M, ETM, ETR>::new_conditional(cb, condition),
+        )
+    }
+
+    /// Registers the provided closure under the provided name.
+    ///
+    /// Triggers built from the same closure object are recognized by the registry.
+    /// The name MUST be unique within this registry.
+    pub fn register_closure<M>(
+        &mut self,
+        name: &'static str,
+        cb: _ClosureTrigger<M>,
+    ) -> Result<(), IDCollisionError<&'static str>>
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        self.register_wrapper(name, TriggerWrapper::<M, ETM, ETR>::new_closure(cb))
+    }
+
+    fn register_wrapper<M>(
+        &mut self,
+        name: &'static str,
+        wrapper: TriggerWrapper<M, ETM, ETR>,
+    ) -> Result<(), IDCollisionError<&'static str>>
+    where
+        M: StateContainer + 'static,
+        M::State: TriggerState,
+        <M::State as TriggerState>::Timing: marker::Timing + IntoEnum<ETM>,
+        <M::State as TriggerState>::Trigger: marker::Triggerable + IntoEnum<ETR>,
+    {
+        if self.entries.iter().any(|&(n, _)| n == name) {
+            return Err(IDCollisionError(name));
+        }
+
+        self.entries.push((name, wrapper.into()));
+        Ok(())
+    }
+
+    /// Retrieves the trigger registered under the provided name.
+    pub fn get(&self, name: &str) -> Option<&UnsafeTrigger<ETM, ETR>> {
+        self.entries
+            .iter()
+            .find(|&&(n, _)| n == name)
+            .map(|&(_, ref t)| t)
+    }
+
+    /// Retrieves the name of the registered callback of the provided trigger.
+    pub fn name_of(&self, trigger: &UnsafeTrigger<ETM, ETR>) -> Option<&'static str> {
+        self.entries
+            .iter()
+            .find(|&&(_, ref t)| {
+                let same_closure = match (&t.closure, &trigger.closure) {
+                    (&Some(ref a), &Some(ref b)) => a.ptr_eq(b),
+                    (&None, &None) => true,
+                    _ => false,
+                };
+                t.func_pointer == trigger.func_pointer && same_closure
+                    && t.condition_pointer == trigger.condition_pointer
+                    && t.machine_family == trigger.machine_family
+                    && t.timing == trigger.timing && t.trigger == trigger.trigger
+            })
+            .map(|&(n, _)| n)
+    }
 }
@@ -0,0 +1,185 @@
+//! Contains functionality to work with zones.
+
+use std::fmt::Debug;
+
+use error::custom_type::ZoneError;
+use function::EntityId;
+use marker;
+use storage::zone::{ZoneMove, ZonePosition, ZoneStorage};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Structure for working with the zones of the state machine.
+///
+/// Each owner (eg a player) has one zone of each kind. Entities are
+/// ordered within their zone and each entity is within one zone at most.
+/// Entities are appended at the end of a zone when no index is provided, the
+/// last entity of a zone is considered it's top.
+pub struct ZoneService<Z>
+where
+    Z: Debug + Copy + Eq,
+{
+    storage: ZoneStorage<Z>,
+    capacities: Vec<(Z, usize)>,
+}
+
+impl<Z> marker::Service for ZoneService<Z> where Z: Debug + Copy + Eq {}
+
+impl<Z> ZoneService<Z>
+where
+    Z: Debug + Copy + Eq,
+{
+    /// Creates a new object of this service.
+    ///
+    /// Zones have no capacity limit by default.
+    pub fn new() -> Self {
+        Self {
+            storage: ZoneStorage::new(),
+            capacities: vec![],
+        }
+    }
+
+    /// Limits the amount of entities each zone of the provided kind can hold.
+    pub fn set_capacity(&mut self, kind: Z, capacity: usize) {
+        self.capacities.retain(|&(k, _)| k != kind);
+        self.capacities.push((kind, capacity));
+    }
+
+    /// Returns the maximum amount of entities each zone of the provided kind can hold.
+    pub fn capacity(&self, kind: Z) -> Option<usize> {
+        self.capacities
+            .iter()
+            .find(|&&(k, _)| k == kind)
+            .map(|&(_, capacity)| capacity)
+    }
+
+    /// Returns all entities within the requested zone, ordered by their position.
+    pub fn entities(&self, owner: EntityId, kind: Z) -> &[EntityId] {
+        self.storage
+            .get(owner, kind)
+            .map(|z| z.entities.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Returns the entity at the top of the requested zone.
+    pub fn top(&self, owner: EntityId, kind: Z) -> Option<EntityId> {
+        self.entities(owner, kind).last().cloned()
+    }
+
+    /// Returns the location of the provided entity.
+    pub fn position_of(&self, entity: EntityId) -> Option<ZonePosition<Z>> {
+        self.storage.find(entity)
+    }
+
+    /// Adds the provided entity, which is not within any zone, to the requested zone.
+    ///
+    /// The entity is inserted at the provided index, or at the top of the zone when
+    /// no index is provided.
+    pub fn insert(
+        &mut self,
+        entity: EntityId,
+        owner: EntityId,
+        kind: Z,
+        index: Option<usize>,
+    ) -> Result<ZonePosition<Z>, ZoneError> {
+        if self.position_of(entity).is_some() {
+            return Err(ZoneError::DuplicateEntity(entity));
+        }
+        let index = self.verify_space(owner, kind, index, false)?;
+        self.storage
+            .get_or_insert_mut(owner, kind)
+            .entities
+            .insert(index, entity);
+        Ok(ZonePosition {
+            owner,
+            zone: kind,
+            index,
+        })
+    }
+
+    /// Removes the provided entity from it's zone.
+    ///
+    /// The last location of the entity is returned.
+    pub fn remove(&mut self, entity: EntityId) -> Result<ZonePosition<Z>, ZoneError> {
+        let position = self
+            .position_of(entity)
+            .ok_or(ZoneError::MissingEntity(entity))?;
+        self.storage
+            .get_or_insert_mut(position.owner, position.zone)
+            .entities
+            .remove(position.index);
+        Ok(position)
+    }
+
+    /// Moves the provided entity into the requested zone.
+    ///
+    /// The entity is inserted at the provided index, or at the top of the zone when
+    /// no index is provided.
+    /// Nothing is moved when the requested zone cannot hold the entity.
+    pub fn move_entity(
+        &mut self,
+        entity: EntityId,
+        owner: EntityId,
+        kind: Z,
+        index: Option<usize>,
+    ) -> Result<ZoneMove<Z>, ZoneError> {
+        let from = self
+            .position_of(entity)
+            .ok_or(ZoneError::MissingEntity(entity))?;
+        let same_zone = from.owner == owner && from.zone == kind;
+        let index = self.verify_space(owner, kind, index, same_zone)?;
+
+        self.remove(entity)?;
+        self.storage
+            .get_or_insert_mut(owner, kind)
+            .entities
+            .insert(index, entity);
+        Ok(ZoneMove {
+            entity,
+            from,
+            into: ZonePosition {
+                owner,
+                zone: kind,
+                index,
+            },
+        })
+    }
+
+    /// Moves the entity at the top of the source zone into the destination zone.
+    pub fn move_top(
+        &mut self,
+        owner: EntityId,
+        from: Z,
+        into: Z,
+    ) -> Result<ZoneMove<Z>, ZoneError> {
+        let entity = self.top(owner, from).ok_or(ZoneError::Empty)?;
+        self.move_entity(entity, owner, into, None)
+    }
+
+    /// Verifies the requested zone can hold one more entity at the provided index.
+    ///
+    /// The index where the entity will be inserted is returned.
+    fn verify_space(
+        &self,
+        owner: EntityId,
+        kind: Z,
+        index: Option<usize>,
+        moves_within: bool,
+    ) -> Result<usize, ZoneError> {
+        let mut len = self.entities(owner, kind).len();
+        if moves_within {
+            // The entity itself is removed before it's inserted again.
+            len -= 1;
+        } else if let Some(capacity) = self.capacity(kind) {
+            if len >= capacity {
+                return Err(ZoneError::Full(capacity));
+            }
+        }
+
+        match index {
+            Some(idx) if idx > len => Err(ZoneError::InvalidPosition(idx)),
+            Some(idx) => Ok(idx),
+            None => Ok(len),
+        }
+    }
+}
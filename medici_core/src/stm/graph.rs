@@ -0,0 +1,341 @@
+//! Runtime representation of the transition graph of a state machine.
+//!
+//! The graph is built from the table generated by [`transitions!`] and allows
+//! inspecting the shape of the machine without reading the trait implementations.
+
+use std::collections::{HashSet, VecDeque};
+use std::fmt::Write;
+
+use stm::transitions::{TransitionEdge, TransitionKind};
+
+/// Registry of all declared transitions between the states of a machine.
+///
+/// States are identified by their human readable name, eg `Trigger<Peri, EndTurn>`.
+///
+/// States of generic edges are templates, eg `Trigger<Pre, TR>`, which stand for many
+/// concrete states at once. Templates are part of the exported graph, the queries about
+/// the shape of the machine resolve them against each concrete state they match, eg
+/// `Effect<A>` matches `Effect<Start>` and `Effect<EndTurn>`.
+#[derive(Debug, Clone)]
+pub struct StateGraph {
+    /// All states in order of first appearance within the declared edges.
+    states: Vec<String>,
+    /// States which refer to generic arguments of their edge.
+    templates: HashSet<String>,
+    /// All declared edges, with normalized state names and the generic arguments
+    /// of the edge.
+    edges: Vec<(TransitionKind, String, String, &'static [&'static str])>,
+}
+
+impl StateGraph {
+    /// Builds the graph from the provided edges.
+    pub fn from_edges<'a, I>(edges: I) -> Self
+    where
+        I: IntoIterator<Item = &'a TransitionEdge>,
+    {
+        let mut graph = StateGraph {
+            states: vec![],
+            templates: HashSet::new(),
+            edges: vec![],
+        };
+
+        for edge in edges {
+            let from = normalize_name(edge.from);
+            let into = normalize_name(edge.into);
+            graph.register_state(&from, edge.generics);
+            graph.register_state(&into, edge.generics);
+            graph.edges.push((edge.kind, from, into, edge.generics));
+        }
+
+        graph
+    }
+
+    fn register_state(&mut self, name: &str, generics: &[&str]) {
+        if !self.states.iter().any(|s| s == name) {
+            self.states.push(name.to_string());
+        }
+        if refers_to_generics(name, generics) {
+            self.templates.insert(name.to_string());
+        }
+    }
+
+    /// Returns the names of all known states, including templates.
+    pub fn states(&self) -> Vec<&str> {
+        self.states.iter().map(String::as_str).collect()
+    }
+
+    /// Returns the names of all template states.
+    pub fn templates(&self) -> Vec<&str> {
+        self.states
+            .iter()
+            .map(String::as_str)
+            .filter(|s| self.is_template(s))
+            .collect()
+    }
+
+    /// Returns true if the provided state is a template of a generic edge.
+    pub fn is_template(&self, state: &str) -> bool {
+        self.templates.contains(state)
+    }
+
+    /// Returns all concrete states, in order of first appearance.
+    fn concrete_states<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        self.states
+            .iter()
+            .map(String::as_str)
+            .filter(move |s| !self.is_template(s))
+    }
+
+    /// Returns all edges between concrete states.
+    ///
+    /// Templates are resolved against each concrete state they match, generic arguments
+    /// shared by both states of an edge are bound to the same value.
+    fn concrete_edges(&self) -> Vec<(TransitionKind, &str, &str)> {
+        let mut resolved = vec![];
+        for &(kind, ref from, ref into, generics) in self.edges.iter() {
+            for concrete_from in self.concrete_states() {
+                let mut bindings = vec![];
+                if !bind_template(from, concrete_from, generics, &mut bindings) {
+                    continue;
+                }
+                for concrete_into in self.concrete_states() {
+                    let mut bindings = bindings.clone();
+                    if bind_template(into, concrete_into, generics, &mut bindings) {
+                        resolved.push((kind, concrete_from, concrete_into));
+                    }
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Returns all edges as (kind, from, into) tuples.
+    pub fn edges<'a>(&'a self) -> impl Iterator<Item = (TransitionKind, &'a str, &'a str)> + 'a {
+        self.edges
+            .iter()
+            .map(|&(kind, ref from, ref into, _)| (kind, from.as_str(), into.as_str()))
+    }
+
+    /// Returns all edges leaving the provided state.
+    pub fn outgoing<'a>(
+        &'a self,
+        state: &'a str,
+    ) -> impl Iterator<Item = (TransitionKind, &'a str)> + 'a {
+        self.edges()
+            .filter(move |&(_, from, _)| from == state)
+            .map(|(kind, _, into)| (kind, into))
+    }
+
+    /// Returns all edges arriving at the provided state.
+    pub fn incoming<'a>(
+        &'a self,
+        state: &'a str,
+    ) -> impl Iterator<Item = (TransitionKind, &'a str)> + 'a {
+        self.edges()
+            .filter(move |&(_, _, into)| into == state)
+            .map(|(kind, from, _)| (kind, from))
+    }
+
+    /// Returns all concrete states which cannot be transitioned into from another
+    /// concrete state.
+    ///
+    /// The initial state of the machine is expected to be part of the result.
+    pub fn states_without_incoming(&self) -> Vec<&str> {
+        let edges = self.concrete_edges();
+        self.concrete_states()
+            .filter(|&s| !edges.iter().any(|&(_, _, into)| into == s))
+            .collect()
+    }
+
+    /// Returns all concrete states which cannot be transitioned out of into another
+    /// concrete state.
+    ///
+    /// The terminal states of the machine are expected to be part of the result.
+    pub fn states_without_outgoing(&self) -> Vec<&str> {
+        let edges = self.concrete_edges();
+        self.concrete_states()
+            .filter(|&s| !edges.iter().any(|&(_, from, _)| from == s))
+            .collect()
+    }
+
+    /// Returns all concrete states which can be reached, by following any kind of edge
+    /// between (resolved) concrete states, from the provided state.
+    ///
+    /// The provided state is only part of the result if it's part of a cycle.
+    /// Nothing is reachable from a template state.
+    pub fn reachable_from(&self, state: &str) -> Vec<&str> {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        let mut reachable = vec![];
+
+        let state = normalize_name(state);
+        let start = match self.concrete_states().find(|s| *s == state) {
+            Some(s) => s,
+            None => return reachable,
+        };
+        queue.push_back(start);
+
+        let edges = self.concrete_edges();
+        while let Some(current) = queue.pop_front() {
+            let next_states = edges
+                .iter()
+                .filter(|&&(_, from, _)| from == current)
+                .map(|&(_, _, into)| into);
+            for next in next_states {
+                if visited.insert(next) {
+                    reachable.push(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        reachable
+    }
+
+    /// Exports the graph in the Graphviz DOT language.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph machine {{").unwrap();
+        for state in self.states.iter() {
+            writeln!(out, "    \"{}\";", state).unwrap();
+        }
+        for &(kind, ref from, ref into, _) in self.edges.iter() {
+            let style = match kind {
+                TransitionKind::Transition => "solid",
+                TransitionKind::Pushdown => "bold",
+                TransitionKind::Pullup => "dashed",
+            };
+            writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}\", style={}];",
+                from,
+                into,
+                kind_label(kind),
+                style
+            ).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// Exports the graph as Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "graph TD").unwrap();
+        // Mermaid identifiers cannot contain generic brackets, so each state
+        // receives a numbered identifier and is labeled with its name.
+        for (idx, state) in self.states.iter().enumerate() {
+            writeln!(out, "    S{}[\"{}\"]", idx, escape_mermaid(state)).unwrap();
+        }
+        for &(kind, ref from, ref into, _) in self.edges.iter() {
+            let arrow = match kind {
+                TransitionKind::Transition => "-->",
+                TransitionKind::Pushdown => "==>",
+                TransitionKind::Pullup => "-.->",
+            };
+            writeln!(
+                out,
+                "    S{} {}|{}| S{}",
+                self.state_index(from),
+                arrow,
+                kind_label(kind),
+                self.state_index(into)
+            ).unwrap();
+        }
+        out
+    }
+
+    fn state_index(&self, name: &str) -> usize {
+        self.states
+            .iter()
+            .position(|s| s == name)
+            .expect("Edges only refer to registered states")
+    }
+}
+
+fn kind_label(kind: TransitionKind) -> &'static str {
+    match kind {
+        TransitionKind::Transition => "transition",
+        TransitionKind::Pushdown => "pushdown",
+        TransitionKind::Pullup => "pullup",
+    }
+}
+
+/// Returns true if the provided state name mentions any of the provided generic
+/// arguments, eg `Trigger<Pre, TR>` for the argument `TR`.
+fn refers_to_generics(name: &str, generics: &[&str]) -> bool {
+    name.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .any(|part| generics.contains(&part))
+}
+
+/// Matches the provided template against the provided concrete state name.
+///
+/// Generic arguments of the template are bound to the part of the concrete name they
+/// match, arguments which are already bound must match their earlier value.
+/// Templates without generic arguments only match the exact same name.
+fn bind_template(
+    template: &str,
+    concrete: &str,
+    generics: &[&str],
+    bindings: &mut Vec<(String, String)>,
+) -> bool {
+    if generics.contains(&template) {
+        if let Some(&(_, ref value)) = bindings.iter().find(|&&(ref g, _)| g == template) {
+            return value == concrete;
+        }
+        bindings.push((template.to_string(), concrete.to_string()));
+        return true;
+    }
+
+    let (template_base, template_args) = split_name(template);
+    let (concrete_base, concrete_args) = split_name(concrete);
+    template_base == concrete_base && template_args.len() == concrete_args.len()
+        && template_args
+            .iter()
+            .zip(concrete_args.iter())
+            .all(|(t, c)| bind_template(t, c, generics, bindings))
+}
+
+/// Splits a state name into its base name and the names of its arguments,
+/// eg `Trigger<Peri, EndTurn>` becomes `Trigger` and `[Peri, EndTurn]`.
+fn split_name(name: &str) -> (&str, Vec<&str>) {
+    let open = match name.find('<') {
+        Some(idx) if name.ends_with('>') => idx,
+        _ => return (name.trim(), vec![]),
+    };
+
+    let inner = &name[open + 1..name.len() - 1];
+    let mut args = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => {
+                args.push(inner[start..idx].trim());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(inner[start..].trim());
+    (name[..open].trim(), args)
+}
+
+fn escape_mermaid(name: &str) -> String {
+    name.replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Normalizes the provided state name so names which were stringified differently
+/// still match, eg `Trigger < Peri , EndTurn >` becomes `Trigger<Peri, EndTurn>`.
+pub fn normalize_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars().filter(|c| !c.is_whitespace()) {
+        out.push(c);
+        if c == ',' {
+            out.push(' ');
+        }
+    }
+    out
+}
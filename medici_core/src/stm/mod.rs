@@ -1,3 +1,6 @@
 //! Traits enforcing state machine behaviour.
 
 pub mod checked;
+pub mod graph;
+#[macro_use]
+pub mod transitions;
@@ -0,0 +1,373 @@
+//! Declarative description of all transitions of a state machine.
+//!
+//! The [`transitions!`] macro implements [`TransitionFrom`], [`PushdownFrom`] and
+//! [`PullupFrom`] for each declared edge and records all edges into a static
+//! table named `TRANSITIONS`.
+
+/// The kind of transition which is performed when following an edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TransitionKind {
+    /// One-way transition, see [`TransitionFrom`].
+    Transition,
+    /// Transition which stores the transaction of the outgoing state, see [`PushdownFrom`].
+    Pushdown,
+    /// Transition which restores a previously pushed down state, see [`PullupFrom`].
+    Pullup,
+}
+
+/// Description of one transition between two states of the machine.
+///
+/// The states are identified by the names used within the [`transitions!`]
+/// invocation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TransitionEdge {
+    /// The kind of transition.
+    pub kind: TransitionKind,
+    /// Name of the state the machine transitions out of.
+    pub from: &'static str,
+    /// Name of the state the machine transitions into.
+    pub into: &'static str,
+    /// Names of the generic arguments the edge was declared with, eg `["TR"]` for
+    /// `for<TR: Triggerable> RecurseEffect<TR> :=: Trigger<Pre, TR>`.
+    pub generics: &'static [&'static str],
+}
+
+/// Macro declaring all valid transitions of a state machine.
+///
+/// The state machine type MUST be built with `#[derive(StateMachine)]` and hold a
+/// [`TransactionStorage`] service for the provided transaction container type.
+///
+/// The following statements are supported, each terminated by a semicolon;
+///     - `A => B => C`, one-way transitions from A into B and from B into C.
+///     - `A :=: B`, pushdown from A into B and pullup from B into A.
+///     - `B <=: A via P`, pullup from B into A without a matching pushdown. The machine
+///     was pushed down from A into P and transitioned from P into B afterwards.
+///     `via P` can be omitted when P is B itself.
+///     - `action X: W1 => W2`, all transitions necessary to process action X which
+///     is started from state W1 and finishes in state W2.
+///     This is a shorthand for the following statements;
+///     `W1 => Action<X> => W2`, `Action<X> :=: Effect<X>` and
+///     `Effect<X> => Trigger<Pre, X> => Trigger<Peri, X> => Trigger<Post, X> => Effect<X>`.
+///
+/// Each transition statement can be made generic by prefixing it with
+/// `for<T: Bound, ..>`. The bound is necessary when the state types themselves
+/// constrain their generic argument.
+///
+/// Each pullup verifies, through the transition history, the state it leaves and the state
+/// it pulls up into. Pulling up from B into A is only possible when the last pushdown went
+/// from A into B, or from A into P for `via P`.
+///
+/// Besides the transition implementations, a constant `TRANSITIONS` is generated which
+/// holds a [`TransitionEdge`] for each declared transition.
+///
+/// # Example
+/// ```ignore
+/// transitions! {
+///     machine = Machine;
+///     transactions = TransactionItem;
+///
+///     action Start: Wait<Start> => Wait<Input>;
+///     action EndTurn: Wait<Input> => Wait<Input>;
+///
+///     for<TR: marker::Triggerable> RecurseEffect<TR> :=: Trigger<Pre, TR>;
+/// }
+/// ```
+#[macro_export]
+macro_rules! transitions {
+    (machine = $machine:ident; transactions = $ttc:ty; $($body:tt)*) => {
+        $crate::__transition_table!(@munch $machine, $ttc; [] $($body)*);
+    };
+}
+
+/// Implementation detail of [`transitions!`].
+///
+/// Munches one statement at a time while accumulating the edges of the graph.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __transition_table {
+    /* All statements are processed */
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]) => {
+        /// All transitions of the state machine.
+        pub const TRANSITIONS: &'static [$crate::stm::transitions::TransitionEdge] = &[$($edges)*];
+    };
+
+    /* Action shorthand */
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]
+        action $act:ident : $wait:ty => $next:ty; $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@transition [] $m;
+            $wait => $crate::prefab::state::Action<$act>);
+        $crate::__transition_table!(@transition [] $m;
+            $crate::prefab::state::Action<$act> => $next);
+        $crate::__transition_table!(@pushdown [] $m, $ttc;
+            $crate::prefab::state::Action<$act> => $crate::prefab::state::Effect<$act>);
+        $crate::__transition_table!(@pullup [] $m, $ttc;
+            $crate::prefab::state::Effect<$act> => $crate::prefab::state::Action<$act>;
+            $crate::prefab::state::Effect<$act>);
+        $crate::__transition_table!(@transition [] $m;
+            $crate::prefab::state::Effect<$act>
+            => $crate::prefab::state::Trigger<$crate::prefab::timing::Pre, $act>);
+        $crate::__transition_table!(@transition [] $m;
+            $crate::prefab::state::Trigger<$crate::prefab::timing::Pre, $act>
+            => $crate::prefab::state::Trigger<$crate::prefab::timing::Peri, $act>);
+        $crate::__transition_table!(@transition [] $m;
+            $crate::prefab::state::Trigger<$crate::prefab::timing::Peri, $act>
+            => $crate::prefab::state::Trigger<$crate::prefab::timing::Post, $act>);
+        $crate::__transition_table!(@transition [] $m;
+            $crate::prefab::state::Trigger<$crate::prefab::timing::Post, $act>
+            => $crate::prefab::state::Effect<$act>);
+
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*
+            $crate::__transition_table!(@edge Transition [],
+                stringify!($wait), concat!("Action<", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Transition [],
+                concat!("Action<", stringify!($act), ">"), stringify!($next)),
+            $crate::__transition_table!(@edge Pushdown [],
+                concat!("Action<", stringify!($act), ">"),
+                concat!("Effect<", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Pullup [],
+                concat!("Effect<", stringify!($act), ">"),
+                concat!("Action<", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Transition [],
+                concat!("Effect<", stringify!($act), ">"),
+                concat!("Trigger<Pre, ", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Transition [],
+                concat!("Trigger<Pre, ", stringify!($act), ">"),
+                concat!("Trigger<Peri, ", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Transition [],
+                concat!("Trigger<Peri, ", stringify!($act), ">"),
+                concat!("Trigger<Post, ", stringify!($act), ">")),
+            $crate::__transition_table!(@edge Transition [],
+                concat!("Trigger<Post, ", stringify!($act), ">"),
+                concat!("Effect<", stringify!($act), ">")),
+        ] $($rest)*);
+    };
+
+    /* (Generic) chains and edges */
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]
+        for<$($g:ident $(: $b:path)*),*> $from:ty => $into:ty; $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@transition [$($g $(: $b)*),*] $m; $from => $into);
+
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*
+            $crate::__transition_table!(@edge Transition [$($g),*],
+                stringify!($from), stringify!($into)),
+        ] $($rest)*);
+    };
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]
+        for<$($g:ident $(: $b:path)*),*> $from:ty => $into:ty => $($chain:tt)*
+    ) => {
+        // Split the chain into the first edge and the remainder.
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*]
+            for<$($g $(: $b)*),*> $from => $into;
+            for<$($g $(: $b)*),*> $into => $($chain)*
+        );
+    };
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]
+        for<$($g:ident $(: $b:path)*),*> $from:ty : = : $into:ty; $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pushdown [$($g $(: $b)*),*] $m, $ttc; $from => $into);
+        $crate::__transition_table!(@pullup [$($g $(: $b)*),*] $m, $ttc; $into => $from; $into);
+
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*
+            $crate::__transition_table!(@edge Pushdown [$($g),*],
+                stringify!($from), stringify!($into)),
+            $crate::__transition_table!(@edge Pullup [$($g),*],
+                stringify!($into), stringify!($from)),
+        ] $($rest)*);
+    };
+
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*]
+        for<$($g:ident $(: $b:path)*),*> $($rest:tt)+
+    ) => {
+        // Types cannot be followed by `<=`, so the states of pullup statements are
+        // collected token by token.
+        $crate::__transition_table!(@pullup_left $m, $ttc; [$($edges)*]
+            [$($g),*] [$($g $(: $b)*),*] [] $($rest)+);
+    };
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*] for $($rest:tt)*) => {
+        compile_error!(concat!("Invalid transition statement: ", stringify!(for $($rest)*)));
+    };
+
+    /* Pullup statements, collects the state which is left */
+    (@pullup_left $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)+] <= : $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pullup_into $m, $ttc; [$($edges)*]
+            [$($g),*] [$($gen)*] [$($left)+] [] $($rest)*);
+    };
+    (@pullup_left $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)*] ; $($rest:tt)*
+    ) => {
+        compile_error!(concat!("Invalid transition statement: ", stringify!($($left)*)));
+    };
+    (@pullup_left $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)*] $next:tt $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pullup_left $m, $ttc; [$($edges)*]
+            [$($g),*] [$($gen)*] [$($left)* $next] $($rest)*);
+    };
+    (@pullup_left $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)*]
+    ) => {
+        compile_error!(concat!("Invalid transition statement: ", stringify!($($left)*)));
+    };
+
+    /* Pullup statements, collects the state which is pulled into */
+    (@pullup_into $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)+] [$($into:tt)+] via $via:ty; $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pullup [$($gen)*] $m, $ttc;
+            $($left)+ => $($into)+; $via);
+
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*
+            $crate::__transition_table!(@edge Pullup [$($g),*],
+                stringify!($($left)+), stringify!($($into)+)),
+        ] $($rest)*);
+    };
+    (@pullup_into $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)+] [$($into:tt)+] ; $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pullup_into $m, $ttc; [$($edges)*]
+            [$($g),*] [$($gen)*] [$($left)+] [$($into)+] via $($left)+; $($rest)*);
+    };
+    (@pullup_into $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)+] [$($into:tt)*] $next:tt $($rest:tt)*
+    ) => {
+        $crate::__transition_table!(@pullup_into $m, $ttc; [$($edges)*]
+            [$($g),*] [$($gen)*] [$($left)+] [$($into)* $next] $($rest)*);
+    };
+    (@pullup_into $m:ident, $ttc:ty; [$($edges:tt)*] [$($g:ident),*] [$($gen:tt)*]
+        [$($left:tt)+] [$($into:tt)*]
+    ) => {
+        compile_error!(concat!(
+            "Invalid transition statement: ",
+            stringify!($($left)+ <=: $($into)*)
+        ));
+    };
+
+    /* Non-generic statements are generic statements without arguments */
+    (@munch $m:ident, $ttc:ty; [$($edges:tt)*] $($rest:tt)+) => {
+        $crate::__transition_table!(@munch $m, $ttc; [$($edges)*] for<> $($rest)+);
+    };
+
+    /* Edge description */
+    (@edge $kind:ident [$($g:ident),*], $from:expr, $into:expr) => {
+        $crate::stm::transitions::TransitionEdge {
+            kind: $crate::stm::transitions::TransitionKind::$kind,
+            from: $from,
+            into: $into,
+            generics: &[$(stringify!($g)),*],
+        }
+    };
+
+    /* Implementation of TransitionFrom */
+    (@transition [$($gen:tt)*] $m:ident; $from:ty => $into:ty) => {
+        impl<CTS, $($gen)*> $crate::stm::checked::TransitionFrom<$m<$from, CTS>, CTS>
+            for $m<$into, CTS>
+        where
+            CTS: $crate::ctstack::CTStack + ::std::marker::Send + 'static,
+            $from: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+            $into: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+        {
+            fn transition_from(
+                old: $m<$from, CTS>,
+                t: <$into as $crate::function::State>::Transaction,
+            ) -> Self {
+                let (_, parts) = old.into_parts();
+                // This transition is declared by the transition table.
+                unsafe { $m::from_parts(t, parts) }
+            }
+        }
+    };
+
+    /* Implementation of PushdownFrom */
+    (@pushdown [$($gen:tt)*] $m:ident, $ttc:ty; $from:ty => $into:ty) => {
+        #[allow(non_camel_case_types)]
+        impl<CTS_OLD, $($gen)*> $crate::stm::checked::PushdownFrom<
+            $m<$from, CTS_OLD>,
+            $crate::ct!($into => CTS_OLD, from $from),
+            $ttc
+        > for $m<$into, $crate::ct!($into => CTS_OLD, from $from)>
+        where
+            CTS_OLD: $crate::ctstack::CTStack + ::std::marker::Send + 'static,
+            $from: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+            $into: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+            <$from as $crate::function::State>::Transaction: ::std::convert::Into<$ttc>,
+            <$into as $crate::function::State>::Transaction: ::std::convert::Into<$ttc>,
+        {
+            fn pushdown_from(
+                old: $m<$from, CTS_OLD>,
+                t: <$into as $crate::function::State>::Transaction,
+            ) -> Self {
+                // Archive state of the old machine.
+                let (old_transaction, parts) = old.into_parts();
+                let old_transaction: $ttc = $crate::transaction::pack_transaction(old_transaction);
+
+                // Build new machine.
+                // This pushdown is declared by the transition table.
+                let mut machine: Self = unsafe { $m::from_parts(t, parts) };
+                $crate::function::StackStorageCompliance::push(
+                    $crate::function::ServiceCompliance::<
+                        $crate::storage::TransactionStorage<$ttc>
+                    >::get_mut(&mut machine),
+                    old_transaction,
+                );
+                machine
+            }
+        }
+    };
+
+    /* Implementation of PullupFrom, out of $left into $into.
+     * $head is the state the last pushdown went into.
+     */
+    (@pullup [$($gen:tt)*] $m:ident, $ttc:ty; $left:ty => $into:ty; $head:ty) => {
+        #[allow(non_camel_case_types)]
+        impl<CTS, $($gen)*> $crate::stm::checked::PullupFrom<$m<$left, CTS>, CTS, $ttc>
+            for $m<$into, <CTS as $crate::ctstack::CTStack>::Tail>
+        where
+            // The history verifies the state which is left, and the state which is
+            // pulled up into is the state the last pushdown started from.
+            CTS: $crate::ctstack::CTStack<Head = $head, Origin = $into>
+                + ::std::marker::Send
+                + 'static,
+            CTS::Tail: ::std::marker::Send,
+            $left: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+            $into: $crate::function::State + $crate::marker::TopLevel + ::std::marker::Send + 'static,
+            <$into as $crate::function::State>::Transaction: ::std::convert::TryFrom<
+                $ttc,
+                Error = $crate::error::custom_type::TransactionUnpackError,
+            >,
+            $m<$left, CTS>: ::std::fmt::Debug + ::std::marker::Send,
+        {
+            fn pullup_from(
+                mut old: $m<$left, CTS>,
+            ) -> ::std::result::Result<Self, $crate::error::MachineError> {
+                use $crate::error::FrontendErrorExt;
+
+                // Retrieve state of the old machine.
+                let old_transaction: $ttc = match $crate::function::StackStorageCompliance::pop(
+                    $crate::function::ServiceCompliance::<
+                        $crate::storage::TransactionStorage<$ttc>
+                    >::get_mut(&mut old),
+                ) {
+                    Ok(v) => v,
+                    Err(e) => return Err(e.infuse($crate::error::ErrorKind::LogicError, || old)),
+                };
+                let old_transaction: <$into as $crate::function::State>::Transaction =
+                    match $crate::transaction::unpack_transaction(old_transaction) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return Err(e.infuse($crate::error::ErrorKind::ConstraintError, || old))
+                        }
+                    };
+
+                // Build new machine.
+                let (_, parts) = old.into_parts();
+                // This pullup is declared by the transition table and verified against
+                // the history of the machine.
+                Ok(unsafe { $m::from_parts(old_transaction, parts) })
+            }
+        }
+    };
+}
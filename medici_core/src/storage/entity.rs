@@ -1,16 +1,69 @@
 //! Module containing structures for storing entities.
+//!
+//! Identifiers of stored entities are generational, the lower half of the bits
+//! of the identifier is the index of the slot holding the entity and the upper
+//! half is the generation of that slot.
+//! Each time an entity is removed the generation of it's slot is incremented, so
+//! identifiers of removed entities never alias entities stored later on in the same slot.
+//!
+//! Identifiers are as wide as [`usize`], so on 32-bit targets at most 65 536 slots can
+//! be addressed and generations wrap around after 65 536 removals from the same slot.
 
+use std::mem;
+
+use error::custom_type::{EntityAccessError, OverflowError};
 use function::{ArrayStorageCompliance, Entity};
 
+/// Amount of bits of an identifier used to encode the slot index.
+const INDEX_BITS: usize = mem::size_of::<usize>() * 4;
+/// Mask selecting the slot index from an identifier.
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+/// Mask selecting the generation after shifting it out of an identifier.
+const GENERATION_MASK: usize = usize::max_value() >> INDEX_BITS;
+
+/// Maximum amount of slots an [`EntityStorage`] can address.
+pub const MAX_ENTITY_SLOTS: usize = INDEX_MASK + 1;
+
+/// Returns the slot index encoded within the provided identifier.
+pub fn id_index(id: usize) -> usize {
+    id & INDEX_MASK
+}
+
+/// Returns the slot generation encoded within the provided identifier.
+pub fn id_generation(id: usize) -> usize {
+    id >> INDEX_BITS
+}
+
+/// Builds an identifier from the provided slot index and generation.
+pub fn build_id(index: usize, generation: usize) -> usize {
+    ((generation & GENERATION_MASK) << INDEX_BITS) | (index & INDEX_MASK)
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Structure wrapping a [`Vec`] to provide a container for (all) entities
 /// within the state machine.
+///
+/// Entities are stored contiguously. Removing an entity moves the last stored entity
+/// into its position and leaves an empty slot behind, which is reused for the next
+/// stored entity.
 pub struct EntityStorage<E>
 where
     E: Entity + Clone,
     E::ID: Into<usize> + From<usize>,
 {
     entities: Vec<E>,
+    slots: Vec<Slot>,
+    free_slots: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Slot addressed by the index of identifiers.
+struct Slot {
+    generation: usize,
+    // Position of the entity within the storage, empty slots have none.
+    position: Option<usize>,
 }
 
 impl<E> EntityStorage<E>
@@ -20,12 +73,160 @@ where
 {
     /// Creates a new object for storage.
     pub fn new() -> Self {
-        Self { entities: vec![] }
+        Self {
+            entities: vec![],
+            slots: vec![],
+            free_slots: vec![],
+        }
+    }
+
+    /// Returns the amount of stored items.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns true if no items are stored.
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns the identifier the next stored item will receive.
+    ///
+    /// [`None`] is returned when no slot can be addressed anymore.
+    pub fn next_id(&self) -> Option<usize> {
+        match self.free_slots.last() {
+            Some(&idx) => Some(build_id(idx, self.slots[idx].generation)),
+            None if self.slots.len() < MAX_ENTITY_SLOTS => Some(build_id(self.slots.len(), 0)),
+            None => None,
+        }
+    }
+
+    /// Stores the item built by the provided function into this object.
+    ///
+    /// The function receives the identifier of the new item, see [`EntityStorage::next_id`].
+    ///
+    /// # Errors
+    /// [`OverflowError`] is returned when no slot can be addressed anymore.
+    pub fn push<F>(&mut self, build: F) -> Result<&mut E, OverflowError>
+    where
+        F: FnOnce(usize) -> E,
+    {
+        let id = self.next_id().ok_or(OverflowError(MAX_ENTITY_SLOTS))?;
+        let item = build(id);
+        let item_id: usize = item.id().into();
+        debug_assert_eq!(item_id, id);
+
+        let idx = id_index(id);
+        if self.free_slots.last() == Some(&idx) {
+            self.free_slots.pop();
+        } else {
+            self.slots.push(Slot {
+                generation: 0,
+                position: None,
+            });
+        }
+        let position = self.entities.len();
+        self.slots[idx].position = Some(position);
+        self.entities.push(item);
+        Ok(&mut self.entities[position])
+    }
+
+    /// Retrieves a reference to the item matching the identifier.
+    ///
+    /// [`None`] is returned for identifiers of removed items.
+    pub fn get(&self, id: usize) -> Option<&E> {
+        let position = self.position(id)?;
+        self.entities.get(position)
+    }
+
+    /// Retrieves a mutable reference to the item matching the identifier.
+    ///
+    /// [`None`] is returned for identifiers of removed items.
+    pub fn get_mut(&mut self, id: usize) -> Option<&mut E> {
+        let position = self.position(id)?;
+        self.entities.get_mut(position)
+    }
+
+    /// Retrieves mutable references to all items matching the identifiers, in the
+    /// same order as the identifiers.
+    ///
+    /// # Errors
+    /// The first identifier which is not matching any item, or which occurs more
+    /// than once, is reported.
+    pub fn get_many_mut(&mut self, ids: &[usize]) -> Result<Vec<&mut E>, EntityAccessError<usize>> {
+        let mut positions = Vec::with_capacity(ids.len());
+        for (n, &id) in ids.iter().enumerate() {
+            let position = match self.position(id) {
+                Some(position) => position,
+                None => return Err(EntityAccessError::Missing(id)),
+            };
+            if ids[..n].contains(&id) {
+                return Err(EntityAccessError::Duplicate(id));
+            }
+            positions.push(position);
+        }
+
+        // The items are split off the storage in order of their position, each
+        // borrow is then returned at the index of its identifier.
+        let mut order: Vec<usize> = (0..ids.len()).collect();
+        order.sort_by_key(|&n| positions[n]);
+        let mut items: Vec<Option<&mut E>> = ids.iter().map(|_| None).collect();
+        let mut rest: &mut [E] = &mut self.entities;
+        let mut offset = 0;
+        for n in order {
+            let (_, tail) = mem::replace(&mut rest, &mut []).split_at_mut(positions[n] - offset);
+            // Positions are verified to be within the storage.
+            let (item, tail) = tail.split_first_mut().unwrap();
+            items[n] = Some(item);
+            rest = tail;
+            offset = positions[n] + 1;
+        }
+        Ok(items.into_iter().map(Option::unwrap).collect())
+    }
+
+    /// Takes the item matching the identifier out of this object.
+    ///
+    /// The last stored item takes the position of the removed item, the slot of
+    /// the removed item is reused for the next stored item.
+    pub fn remove(&mut self, id: usize) -> Option<E> {
+        let idx = self.live_index(id)?;
+        let position = self.slots[idx].position.take()?;
+        let item = self.entities.swap_remove(position);
+        // Only the previously last item changed position.
+        if let Some(moved) = self.entities.get(position) {
+            let moved_id: usize = moved.id().into();
+            self.slots[id_index(moved_id)].position = Some(position);
+        }
+
+        let slot = &mut self.slots[idx];
+        slot.generation = slot.generation.wrapping_add(1) & GENERATION_MASK;
+        self.free_slots.push(idx);
+        Some(item)
+    }
+
+    /// Returns an iterator over all stored items.
+    pub fn iter(&self) -> impl Iterator<Item = &E> {
+        self.entities.iter()
+    }
+
+    /// Returns an iterator over mutable references to all stored items.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut E> {
+        self.entities.iter_mut()
+    }
+
+    /// Returns the slot index of the identifier if the generation matches.
+    fn live_index(&self, id: usize) -> Option<usize> {
+        let idx = id_index(id);
+        match self.slots.get(idx) {
+            Some(slot) if slot.generation == id_generation(id) => Some(idx),
+            _ => None,
+        }
     }
 
-    /// Stores the provided item into this object.
-    pub fn push(&mut self, item: E) {
-        self.entities.push(item)
+    /// Returns the position of the item matching the identifier.
+    fn position(&self, id: usize) -> Option<usize> {
+        let idx = self.live_index(id)?;
+        self.slots[idx].position
     }
 }
 
@@ -5,6 +5,7 @@ use function::StackStorageCompliance;
 use marker;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
 /// Object for storing [`Transaction`] objects.
 ///
 /// This structure implements a stack contract to allow pushing
@@ -28,6 +29,14 @@ where
     }
 }
 
+// The storage is also a service because state machines hook it up to move their
+// transactions between pushdown and pullup transitions.
+impl<TTC> marker::Service for TransactionStorage<TTC>
+where
+    TTC: marker::TransactionContainer + Clone,
+{
+}
+
 impl<TTC> StackStorageCompliance for TransactionStorage<TTC>
 where
     TTC: marker::TransactionContainer + Clone,
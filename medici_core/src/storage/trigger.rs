@@ -1,9 +1,59 @@
 //! Module containing types for storing game triggers.
 
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::fmt;
 use std::marker::PhantomData;
+use std::sync::Arc;
 
+use function::EntityId;
 use marker;
 
+/// Identifier of a trigger registered with a [`TriggerService`].
+///
+/// Handles are unique within the service the trigger was registered with.
+/// The zero handle is never handed out, it marks triggers which are not (yet)
+/// stored within a service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+pub struct TriggerHandle(pub(crate) u64);
+
+impl fmt::Display for TriggerHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<Trigger(ID={})>", self.0)
+    }
+}
+
+impl TriggerHandle {
+    /// Returns the numeric value of this handle.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Type-erased closure callback of a trigger, see [`UnsafeTrigger::closure`].
+#[derive(Clone)]
+pub struct ErasedClosure {
+    /// The shared closure object, typed for the machine it was built for.
+    pub(crate) closure: Arc<Any + Send + Sync>,
+    /// Address of the closure itself, which is shared between all triggers
+    /// built from the same closure.
+    pub(crate) key: usize,
+}
+
+impl fmt::Debug for ErasedClosure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ErasedClosure(0x{:x})", self.key)
+    }
+}
+
+impl ErasedClosure {
+    /// Returns true if both objects refer to the same closure.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
 /// Structure serializng/generalizing a trigger.
 ///
 /// The state machine will use this abstraction to store a specific
@@ -16,6 +66,21 @@ where
     ETM: marker::TimingEnumerator + Copy,
     ETR: marker::TriggerEnumerator + Copy,
 {
+    /// Identifier of this trigger within the service it's stored in.
+    pub handle: TriggerHandle,
+    /// Optional human readable name of this trigger.
+    pub name: Option<Cow<'static, str>>,
+    /// Optional entity which registered this trigger.
+    pub owner: Option<EntityId>,
+    /// Priority of this trigger, triggers with a higher priority are executed first.
+    pub priority: i32,
+    /// Ordering key of this trigger, used to order triggers with equal priority.
+    ///
+    /// Eg: The turn on which the owning entity was played.
+    pub order: u64,
+    /// Fingerprint of the state machine the callback was built for,
+    /// see [`StateContainer::Family`].
+    pub machine_family: TypeId,
     /// (Sized) Timing value belonging to the callback, see func_pointer.
     pub timing: ETM,
     /// (Sized) Trigger value belonging to the callback, see func_pointer.
@@ -23,7 +88,16 @@ where
     /// The callback pointer which must be transmuted and executed
     /// when the conditions of the running state machine match the
     /// ones contained within this structure.
+    ///
+    /// This pointer is null when the callback is a closure, see closure.
     pub func_pointer: *const (),
+    /// The closure callback which must be executed when the conditions of the
+    /// running state machine match the ones contained within this structure.
+    pub closure: Option<ErasedClosure>,
+    /// Optional pointer to the condition method which must hold before the
+    /// callback is executed.
+    /// The condition receives the same state machine as the callback.
+    pub condition_pointer: Option<*const ()>,
 
     // This field prevents UnsafeTrigger from being constructed by framework
     // users.
@@ -58,6 +132,8 @@ where
     ///
     /// TODO; Find out if splitting this up is useful + find out how.
     pub triggers: Vec<UnsafeTrigger<ETM, ETR>>,
+    /// Value of the handle which will be assigned to the next stored trigger.
+    pub(crate) next_handle: u64,
 }
 
 impl<ETM, ETR> TriggerStorage<ETM, ETR>
@@ -67,6 +143,18 @@ where
 {
     /// Builds a new object for storage.
     pub fn new() -> Self {
-        Self { triggers: vec![] }
+        Self {
+            triggers: vec![],
+            next_handle: 1,
+        }
+    }
+
+    /// Stores the provided trigger and assigns it a new handle.
+    pub fn push(&mut self, mut trigger: UnsafeTrigger<ETM, ETR>) -> TriggerHandle {
+        let handle = TriggerHandle(self.next_handle);
+        self.next_handle += 1;
+        trigger.handle = handle;
+        self.triggers.push(trigger);
+        handle
     }
 }
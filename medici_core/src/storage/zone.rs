@@ -0,0 +1,117 @@
+//! Module containing structures for storing zones.
+//!
+//! A zone is an ordered collection of entities owned by another entity, eg the
+//! hand of a player.
+
+use std::fmt::Debug;
+
+use function::EntityId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Location of an entity within the zones of the state machine.
+pub struct ZonePosition<Z> {
+    /// The entity owning the zone.
+    pub owner: EntityId,
+    /// The kind of zone.
+    pub zone: Z,
+    /// Index of the entity within the zone.
+    pub index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Description of an entity which moved between zones.
+pub struct ZoneMove<Z> {
+    /// The moved entity.
+    pub entity: EntityId,
+    /// Location of the entity before moving.
+    pub from: ZonePosition<Z>,
+    /// Location of the entity after moving.
+    pub into: ZonePosition<Z>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Ordered collection of entities.
+pub struct Zone<Z> {
+    /// The entity owning this zone.
+    pub owner: EntityId,
+    /// The kind of this zone.
+    pub kind: Z,
+    /// Entities within this zone, ordered by their position.
+    pub entities: Vec<EntityId>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde-support", derive(Serialize, Deserialize))]
+/// Structure holding onto all zones of the state machine.
+///
+/// Zones are created the first time they're requested mutably.
+pub struct ZoneStorage<Z>
+where
+    Z: Debug + Copy + Eq,
+{
+    zones: Vec<Zone<Z>>,
+}
+
+impl<Z> ZoneStorage<Z>
+where
+    Z: Debug + Copy + Eq,
+{
+    /// Creates a new object for storage.
+    pub fn new() -> Self {
+        Self { zones: vec![] }
+    }
+
+    /// Retrieves a reference to the requested zone, if it exists.
+    pub fn get(&self, owner: EntityId, kind: Z) -> Option<&Zone<Z>> {
+        self.zones
+            .iter()
+            .find(|z| z.owner == owner && z.kind == kind)
+    }
+
+    /// Retrieves a mutable reference to the requested zone.
+    ///
+    /// A new, empty, zone is created if it doesn't exist yet.
+    pub fn get_or_insert_mut(&mut self, owner: EntityId, kind: Z) -> &mut Zone<Z> {
+        let existing = self
+            .zones
+            .iter()
+            .position(|z| z.owner == owner && z.kind == kind);
+        let idx = match existing {
+            Some(idx) => idx,
+            None => {
+                self.zones.push(Zone {
+                    owner,
+                    kind,
+                    entities: vec![],
+                });
+                self.zones.len() - 1
+            }
+        };
+        &mut self.zones[idx]
+    }
+
+    /// Returns the location of the provided entity.
+    pub fn find(&self, entity: EntityId) -> Option<ZonePosition<Z>> {
+        self.zones
+            .iter()
+            .filter_map(|z| {
+                z.entities
+                    .iter()
+                    .position(|&e| e == entity)
+                    .map(|index| ZonePosition {
+                        owner: z.owner,
+                        zone: z.kind,
+                        index,
+                    })
+            })
+            .next()
+    }
+
+    /// Returns an iterator over all zones.
+    pub fn iter(&self) -> impl Iterator<Item = &Zone<Z>> {
+        self.zones.iter()
+    }
+}
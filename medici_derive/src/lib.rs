@@ -1,3 +1,177 @@
+// Quote recurses for each token tree, generated implementations are big.
+#![recursion_limit = "256"]
+
+//! Procedural macros which generate the boilerplate necessary for building state
+//! machines with medici_core.
+//!
+//! The generated code refers to the medici_core crate by its absolute path, so
+//! the crate using these macros MUST have `extern crate medici_core` at its root.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+mod prototype;
+mod state;
+mod state_machine;
+mod transaction;
+
+use proc_macro::TokenStream;
+
+/// Derives the state machine boilerplate for the annotated structure.
+///
+/// The structure MUST contain the fields `state`, `history` (both [`PhantomData`])
+/// and `transaction`. All other fields are considered parts of the machine which are
+/// moved unaltered between states.
+///
+/// The following items are generated;
+///     - `StateContainer` implementation,
+///     - `ServiceCompliance<S>` implementation for each field marked with `#[service]`,
+///     - `into_parts` and `from_parts` methods which move all parts of the machine
+///     into a new machine, regardless of it's state.
+///     - `StateContainer::Normalized`, the machine with its transition history
+///     replaced by `AnyStack`, together with the conversions from and into it.
+///
+/// # Attributes
+/// `#[state_machine(timing = "..", trigger = "..")]` is required on the structure and
+/// defines the timing- and trigger enumerations of the machine.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Debug, Clone, StateMachine)]
+/// #[state_machine(timing = "TimingItem", trigger = "TriggerItem")]
+/// pub struct Machine<X, CTS>
+/// where
+///     X: marker::TopLevel + State + Send,
+///     CTS: CTStack + Send,
+/// {
+///     state: PhantomData<X>,
+///     history: PhantomData<CTS>,
+///     pub transaction: X::Transaction,
+///     #[service]
+///     pub entities: EntityService<Entity>,
+/// }
+/// ```
+#[proc_macro_derive(StateMachine, attributes(state_machine, service))]
+pub fn derive_state_machine(input: TokenStream) -> TokenStream {
+    let input: syn::DeriveInput = syn::parse(input).expect("Failed to parse derive input");
+    state_machine::expand(&input).into()
+}
+
+/// Derives the [`State`] implementation of the annotated structure.
+///
+/// The name of the state is the name of the structure. Structures which are generic
+/// over other states compose their name from the names of their type arguments,
+/// eg `Wait<Start>`, so each type argument MUST implement [`State`].
+///
+/// # Attributes
+/// `#[state(transaction = "..")]` is required on the structure and defines the
+/// transaction type of the state.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Debug, Clone, State)]
+/// #[state(transaction = "transaction::Death")]
+/// pub struct Death();
+/// ```
+#[proc_macro_derive(State, attributes(state))]
+pub fn derive_state(input: TokenStream) -> TokenStream {
+    let input: syn::DeriveInput = syn::parse(input).expect("Failed to parse derive input");
+    state::expand(&input).into()
+}
+
+/// Derives the conversions between a transaction container and the transactions
+/// it wraps.
+///
+/// The annotated enumeration MUST wrap exactly one [`Transaction`] within each variant.
+///
+/// The following items are generated;
+///     - `TransactionContainer` implementation,
+///     - `From<T>` implementation for each wrapped transaction type `T`,
+///     - `TryFrom<Container>` implementation for each wrapped transaction type `T`,
+///     the error names the expected and provided variant,
+///     - `variant_name` method returning the name of the wrapping variant.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Debug, Clone, TransactionContainer)]
+/// pub enum TransactionItem {
+///     Epsilon(Epsilon),
+///     Damage(Damage),
+/// }
+/// ```
+#[proc_macro_derive(TransactionContainer)]
+pub fn derive_transaction_container(input: TokenStream) -> TokenStream {
+    let input: syn::DeriveInput = syn::parse(input).expect("Failed to parse derive input");
+    transaction::expand(&input).into()
+}
+
+/// Derives the boilerplate of a prototype structure.
+///
+/// The annotated structure MUST be generic over a lifetime and an [`Entity`] type, and
+/// wrap exactly one `Either<'a, E>`.
+///
+/// The following items are generated;
+///     - `Prototype` implementation,
+///     - `From<&'a E>` and `From<&'a mut E>` implementations,
+///     - `IntoEnum<P>` implementation, where `P` is the enumeration of all prototypes.
+///
+/// # Attributes
+/// `#[prototype(P)]` is required on the structure and names the enumeration of all
+/// prototypes, see [`prototypes!`].
+/// The variant within `P` MUST have the same name as the annotated structure.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Debug, Prototype)]
+/// #[prototype(ProtoItem)]
+/// pub struct GameProto<'a, E: Entity + 'a>(pub Either<'a, E>);
+/// ```
+#[proc_macro_derive(Prototype, attributes(prototype))]
+pub fn derive_prototype(input: TokenStream) -> TokenStream {
+    let input: syn::DeriveInput = syn::parse(input).expect("Failed to parse derive input");
+    prototype::expand(&input).into()
+}
+
+/// Builds the enumeration of all prototypes.
+///
+/// The provided enumeration MUST only contain unit variants, one for each prototype.
+/// The necessary traits are derived and `ProtoEnumerator` is implemented.
+///
+/// # Attributes
+/// `#[prototype(E)]` is optional on the enumeration and names the entity type of the
+/// machine. When present, the following items are generated for each variant `XProto`;
+///     - the structure `XProto<'a, E>` wrapping one `Either<'a, E>`, carrying the
+///     documentation of the variant,
+///     - the alias `X<'a>` of the structure targetting the entity type,
+///     - everything generated by [`Prototype`].
+///
+/// # Example
+/// ```ignore
+/// prototypes! {
+///     /// All prototypes of the machine.
+///     #[prototype(MachineEntity)]
+///     pub enum ProtoItem {
+///         /// Prototype for game related behaviour.
+///         GameProto,
+///         /// Prototype for player related behaviour.
+///         PlayerProto,
+///     }
+/// }
+///
+/// // Generated prototype structures are used like any other.
+/// impl<'a> Game<'a> {
+///     // ..
+/// }
+/// ```
+#[proc_macro]
+pub fn prototypes(input: TokenStream) -> TokenStream {
+    let item: syn::ItemEnum = syn::parse(input).expect("Expected an enumeration of prototypes");
+    prototype::expand_enumerator(&item).into()
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
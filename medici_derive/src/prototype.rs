@@ -0,0 +1,194 @@
+//! Implementation of `#[derive(Prototype)]` and `prototypes!`.
+
+use quote::Tokens;
+use syn::{self, Attribute, Data, DeriveInput, Fields, GenericParam, Generics, Ident, ItemEnum,
+          Lifetime, Meta, NestedMeta};
+
+/// Attribute defining the enumeration of all prototypes, or the entity type
+/// targetted by generated prototypes.
+const PROTOTYPE_ATTRIBUTE: &str = "prototype";
+/// Suffix of each prototype name, which is stripped to build the alias name.
+const PROTOTYPE_SUFFIX: &str = "Proto";
+
+pub fn expand(input: &DeriveInput) -> Tokens {
+    let name = &input.ident;
+    let enumerator = prototype_argument(&input.attrs).unwrap_or_else(|| {
+        panic!(
+            "Missing `{}(..)` attribute naming the prototype enumeration",
+            PROTOTYPE_ATTRIBUTE
+        )
+    });
+
+    match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {}
+            _ => panic!("Prototype `{}` MUST wrap exactly one `Either<'a, E>`", quote!(#name)),
+        },
+        _ => panic!("Prototype can only be derived for structures"),
+    };
+
+    implementations(name, &input.generics, &enumerator)
+}
+
+/// Builds the enumeration of all prototypes from the provided (unit-only) enumeration.
+///
+/// When the enumeration is annotated with `#[prototype(E)]` a prototype structure,
+/// and an alias targetting E, is generated for each variant as well.
+pub fn expand_enumerator(item: &ItemEnum) -> Tokens {
+    let name = &item.ident;
+    for variant in item.variants.iter() {
+        match variant.fields {
+            Fields::Unit => {}
+            _ => {
+                let ident = &variant.ident;
+                panic!("Prototype variant `{}` MUST NOT hold data", quote!(#ident));
+            }
+        }
+    }
+
+    let entity = prototype_argument(&item.attrs);
+    let prototypes: Vec<Tokens> = match entity {
+        Some(ref entity) => item.variants
+            .iter()
+            .map(|v| expand_prototype(item, &v.ident, &v.attrs, entity))
+            .collect(),
+        None => vec![],
+    };
+
+    // The attribute is consumed by this macro and cannot be emitted.
+    let mut item = item.clone();
+    item.attrs.retain(|a| !is_attribute(a, PROTOTYPE_ATTRIBUTE));
+
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #item
+
+        impl ::medici_core::marker::ProtoEnumerator for #name {}
+
+        #(#prototypes)*
+    }
+}
+
+/// Generates the prototype structure `name`, its alias targetting `entity` and
+/// all implementations of `#[derive(Prototype)]`.
+fn expand_prototype(item: &ItemEnum, name: &Ident, attrs: &[Attribute], entity: &Ident) -> Tokens {
+    let vis = &item.vis;
+    let enumerator = &item.ident;
+    let docs: Vec<&Attribute> = attrs.iter().filter(|a| is_attribute(a, "doc")).collect();
+
+    let alias = {
+        let name: &str = name.as_ref();
+        if !name.ends_with(PROTOTYPE_SUFFIX) || name == PROTOTYPE_SUFFIX {
+            panic!("Prototype variant `{}` MUST end with `{}`", name, PROTOTYPE_SUFFIX);
+        }
+        Ident::from(&name[..name.len() - PROTOTYPE_SUFFIX.len()])
+    };
+    let alias_doc = format!(
+        "Prototype [`{}`] specifically targetting [`{}`].",
+        name.as_ref() as &str,
+        entity.as_ref() as &str
+    );
+
+    let structure = quote! {
+        #(#docs)*
+        #[derive(Debug)]
+        #vis struct #name<'a, E: ::medici_core::function::Entity + 'a>(
+            pub ::medici_core::prefab::prototype::Either<'a, E>,
+        );
+    };
+    let generics = syn::parse2::<DeriveInput>(structure.clone().into())
+        .expect("Failed to parse generated prototype")
+        .generics;
+    let implementations = implementations(name, &generics, enumerator);
+
+    quote! {
+        #structure
+
+        #[doc = #alias_doc]
+        #vis type #alias<'a> = #name<'a, #entity>;
+
+        #implementations
+    }
+}
+
+/// Implements `Prototype`, `From<&'a E>`, `From<&'a mut E>` and `IntoEnum<P>`
+/// for the prototype `name`.
+fn implementations(name: &Ident, generics: &Generics, enumerator: &Ident) -> Tokens {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (lifetime, entity) = reference_arguments(generics);
+
+    quote! {
+        impl #impl_generics ::medici_core::marker::Prototype for #name #ty_generics #where_clause {}
+
+        impl #impl_generics ::std::convert::From<&#lifetime #entity>
+            for #name #ty_generics #where_clause
+        {
+            fn from(x: &#lifetime #entity) -> Self {
+                #name(::medici_core::prefab::prototype::Either::Imut(x))
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<&#lifetime mut #entity>
+            for #name #ty_generics #where_clause
+        {
+            fn from(x: &#lifetime mut #entity) -> Self {
+                #name(::medici_core::prefab::prototype::Either::Mut(x))
+            }
+        }
+
+        impl #impl_generics ::medici_core::value_from_type_traits::IntoEnum<#enumerator>
+            for #name #ty_generics #where_clause
+        {
+            fn into_enum() -> #enumerator {
+                #enumerator::#name
+            }
+        }
+    }
+}
+
+/// Retrieves the lifetime and entity type argument of the prototype structure,
+/// eg `'a` and `E` within `GameProto<'a, E>`.
+fn reference_arguments(generics: &Generics) -> (&Lifetime, &Ident) {
+    let mut lifetime = None;
+    let mut entity = None;
+    for param in generics.params.iter() {
+        match *param {
+            GenericParam::Lifetime(ref def) if lifetime.is_none() => lifetime = Some(&def.lifetime),
+            GenericParam::Type(ref def) if entity.is_none() => entity = Some(&def.ident),
+            _ => {}
+        }
+    }
+
+    (
+        lifetime.expect("A prototype MUST have a lifetime argument, eg `Proto<'a, E>`"),
+        entity.expect("A prototype MUST have an entity type argument, eg `Proto<'a, E>`"),
+    )
+}
+
+/// Returns true if the provided attribute is named `name`, eg `doc` for doc comments.
+fn is_attribute(attr: &Attribute, name: &str) -> bool {
+    attr.path.segments.len() == 1 && attr.path.segments[0].ident.as_ref() as &str == name
+}
+
+/// Parses `#[prototype(Argument)]` into the identifier of the argument.
+fn prototype_argument(attrs: &[Attribute]) -> Option<Ident> {
+    for meta in attrs.iter().filter_map(|a| a.interpret_meta()) {
+        let list = match meta {
+            Meta::List(list) => list,
+            _ => continue,
+        };
+        {
+            let ident: &str = list.ident.as_ref();
+            if ident != PROTOTYPE_ATTRIBUTE {
+                continue;
+            }
+        }
+
+        return match list.nested.iter().next() {
+            Some(&NestedMeta::Meta(Meta::Word(ref ident))) => Some(ident.clone()),
+            _ => panic!("Expected `{}(Identifier)`", PROTOTYPE_ATTRIBUTE),
+        };
+    }
+
+    None
+}
@@ -0,0 +1,79 @@
+//! Implementation of `#[derive(State)]`.
+
+use quote::Tokens;
+use syn::{self, Attribute, DeriveInput, GenericParam, Lit, Meta, NestedMeta, Type};
+
+/// Attribute defining the transaction type of the state.
+const STATE_ATTRIBUTE: &str = "state";
+
+pub fn expand(input: &DeriveInput) -> Tokens {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let transaction = state_transaction(&input.attrs);
+
+    // The name is composed from the names of all type arguments, eg `Wait<Start>`.
+    let arguments: Vec<_> = input
+        .generics
+        .params
+        .iter()
+        .filter_map(|param| match *param {
+            GenericParam::Type(ref def) => Some(&def.ident),
+            _ => None,
+        })
+        .collect();
+    let name_str: &str = name.as_ref();
+    let name_body = if arguments.is_empty() {
+        quote! { #name_str.into() }
+    } else {
+        let placeholders = vec!["{}"; arguments.len()].join(", ");
+        let format_str = format!("{}<{}>", name_str, placeholders);
+        quote! {
+            format!(#format_str, #(<#arguments as ::medici_core::function::State>::name()),*)
+        }
+    };
+
+    quote! {
+        impl #impl_generics ::medici_core::function::State for #name #ty_generics #where_clause {
+            type Transaction = #transaction;
+
+            fn name() -> String {
+                #name_body
+            }
+        }
+    }
+}
+
+/// Parses `#[state(transaction = "..")]` into the transaction type of the state.
+fn state_transaction(attrs: &[Attribute]) -> Type {
+    for meta in attrs.iter().filter_map(|a| a.interpret_meta()) {
+        let list = match meta {
+            Meta::List(list) => list,
+            _ => continue,
+        };
+        {
+            let ident: &str = list.ident.as_ref();
+            if ident != STATE_ATTRIBUTE {
+                continue;
+            }
+        }
+
+        for nested in list.nested.iter() {
+            let name_value = match *nested {
+                NestedMeta::Meta(Meta::NameValue(ref name_value)) => name_value,
+                _ => panic!("Expected `{}(transaction = \"..\")`", STATE_ATTRIBUTE),
+            };
+            let key: &str = name_value.ident.as_ref();
+            if key != "transaction" {
+                panic!("Unknown {} key `{}`", STATE_ATTRIBUTE, key);
+            }
+
+            return match name_value.lit {
+                Lit::Str(ref value) => syn::parse_str(&value.value())
+                    .expect("The provided string is not a valid type"),
+                _ => panic!("Expected a type wrapped in a string literal"),
+            };
+        }
+    }
+
+    panic!("Missing `{}(transaction = \"..\")` attribute", STATE_ATTRIBUTE)
+}
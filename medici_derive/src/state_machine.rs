@@ -0,0 +1,270 @@
+//! Implementation of `#[derive(StateMachine)]`.
+
+use quote::Tokens;
+use syn::{self, Attribute, Data, DeriveInput, Field, Fields, GenericArgument, GenericParam,
+          Ident, Lit, Meta, NestedMeta, PathArguments, Type};
+
+/// Field encoding the current state of the machine.
+const STATE_FIELD: &str = "state";
+/// Field encoding the transition history of the machine.
+const HISTORY_FIELD: &str = "history";
+/// Field holding the transaction object of the current state.
+const TRANSACTION_FIELD: &str = "transaction";
+
+/// Attribute defining the enumerations of the machine.
+const MACHINE_ATTRIBUTE: &str = "state_machine";
+/// Attribute marking a field as service of the machine.
+const SERVICE_ATTRIBUTE: &str = "service";
+
+pub fn expand(input: &DeriveInput) -> Tokens {
+    let name = &input.ident;
+    let vis = &input.vis;
+    // Marker type shared by all states of the machine, see `StateContainer::Family`.
+    let family = Ident::from(&*format!("__{}Family", name.as_ref() as &str));
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let fields: Vec<&Field> = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => fields.named.iter().collect(),
+            _ => panic!("StateMachine can only be derived for structures with named fields"),
+        },
+        _ => panic!("StateMachine can only be derived for structures"),
+    };
+
+    let state_ty = phantom_argument(required_field(&fields, STATE_FIELD));
+    let history_ty = phantom_argument(required_field(&fields, HISTORY_FIELD));
+    let transaction_ty = &required_field(&fields, TRANSACTION_FIELD).ty;
+    let (timing_ty, trigger_ty) = machine_enumerations(&input.attrs);
+    let normalized_ty = normalized_type(input, history_ty);
+
+    // Each service receives it's own implementation of ServiceCompliance.
+    let service_impls: Vec<Tokens> = fields
+        .iter()
+        .filter(|f| has_word_attribute(&f.attrs, SERVICE_ATTRIBUTE))
+        .map(|f| {
+            let ident = f.ident.as_ref().unwrap();
+            let ty = &f.ty;
+            quote! {
+                impl #impl_generics ::medici_core::function::ServiceCompliance<#ty>
+                    for #name #ty_generics #where_clause
+                {
+                    fn get(&self) -> &#ty {
+                        &self.#ident
+                    }
+
+                    fn get_mut(&mut self) -> &mut #ty {
+                        &mut self.#ident
+                    }
+                }
+            }
+        })
+        .collect();
+
+    // All fields which are not required are parts of the machine. These are moved
+    // into the next machine when transitioning.
+    let parts: Vec<&Field> = fields
+        .iter()
+        .cloned()
+        .filter(|f| !is_required_field(f))
+        .collect();
+    let part_idents: Vec<&Ident> = parts.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let part_types: Vec<&Type> = parts.iter().map(|f| &f.ty).collect();
+    // Quote consumes each interpolated iterator, references are used so the same
+    // collection can be repeated multiple times.
+    let part_idents = &part_idents;
+    let part_types = &part_types;
+
+    quote! {
+        /// Marker type identifying all states of the machine.
+        #[doc(hidden)]
+        #[derive(Debug)]
+        #vis struct #family;
+
+        impl #impl_generics ::medici_core::function::StateContainer
+            for #name #ty_generics #where_clause
+        {
+            type Family = #family;
+            type State = #state_ty;
+            type TransitionRecord = #history_ty;
+            type TimingEnum = #timing_ty;
+            type TriggerEnum = #trigger_ty;
+            type Normalized = #normalized_ty;
+
+            fn into_normalized(self) -> Self::Normalized {
+                let (transaction, parts) = self.into_parts();
+                // The normalized machine is only used to erase the state of this machine.
+                unsafe { <#normalized_ty>::from_parts(transaction, parts) }
+            }
+
+            fn from_normalized(x: Self::Normalized) -> Self {
+                let (transaction, parts) = x.into_parts();
+                // The normalized machine was built from a machine in this state.
+                unsafe { Self::from_parts(transaction, parts) }
+            }
+        }
+
+        #(#service_impls)*
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Splits this machine into the transaction of the current state and all
+            /// other parts of the machine.
+            ///
+            /// Use [`from_parts`] to build a machine in another state from the
+            /// returned parts.
+            pub fn into_parts(self) -> (#transaction_ty, (#(#part_types,)*)) {
+                (self.transaction, (#(self.#part_idents,)*))
+            }
+
+            /// Builds a machine from the provided transaction and the parts of
+            /// another machine, see [`into_parts`].
+            ///
+            /// # Safety
+            /// The new machine is put into any state, with any transition history,
+            /// bypassing the transitions which are declared for this machine.
+            /// Callers MUST guarantee the machine the parts were taken from could
+            /// legally transition into the requested state and history.
+            /// Use [`TransitionFrom`], [`PushdownFrom`] or [`PullupFrom`] instead.
+            pub unsafe fn from_parts(
+                transaction: #transaction_ty,
+                parts: (#(#part_types,)*),
+            ) -> Self {
+                let (#(#part_idents,)*) = parts;
+                #name {
+                    state: ::std::marker::PhantomData,
+                    history: ::std::marker::PhantomData,
+                    transaction,
+                    #(#part_idents,)*
+                }
+            }
+        }
+    }
+}
+
+/// Returns true if the provided field is one of the fields each state machine
+/// MUST define.
+fn is_required_field(field: &Field) -> bool {
+    [STATE_FIELD, HISTORY_FIELD, TRANSACTION_FIELD]
+        .iter()
+        .any(|name| is_field_named(field, name))
+}
+
+fn is_field_named(field: &Field, name: &str) -> bool {
+    match field.ident {
+        Some(ref ident) => {
+            let ident: &str = ident.as_ref();
+            ident == name
+        }
+        None => false,
+    }
+}
+
+fn required_field<'a>(fields: &[&'a Field], name: &str) -> &'a Field {
+    fields
+        .iter()
+        .cloned()
+        .find(|f| is_field_named(f, name))
+        .unwrap_or_else(|| panic!("A state machine MUST have a field named `{}`", name))
+}
+
+/// Returns the type of the machine where the generic argument of the transition
+/// history is replaced by `AnyStack`.
+fn normalized_type(input: &DeriveInput, history_ty: &Type) -> Tokens {
+    let name = &input.ident;
+    let history = quote!(#history_ty).to_string();
+    let arguments: Vec<Tokens> = input
+        .generics
+        .params
+        .iter()
+        .map(|param| match *param {
+            GenericParam::Type(ref param) => {
+                let ident = &param.ident;
+                if quote!(#ident).to_string() == history {
+                    quote!(::medici_core::ctstack::AnyStack)
+                } else {
+                    quote!(#ident)
+                }
+            }
+            GenericParam::Lifetime(ref param) => {
+                let lifetime = &param.lifetime;
+                quote!(#lifetime)
+            }
+            GenericParam::Const(ref param) => {
+                let ident = &param.ident;
+                quote!(#ident)
+            }
+        })
+        .collect();
+    quote!(#name<#(#arguments),*>)
+}
+
+/// Retrieves the type argument `X` from the field type `PhantomData<X>`.
+fn phantom_argument(field: &Field) -> &Type {
+    if let Type::Path(ref type_path) = field.ty {
+        if let Some(segment) = type_path.path.segments.iter().last() {
+            if let PathArguments::AngleBracketed(ref arguments) = segment.arguments {
+                if let Some(&GenericArgument::Type(ref inner)) = arguments.args.iter().next() {
+                    return inner;
+                }
+            }
+        }
+    }
+    let ident = &field.ident;
+    panic!("The type of field `{}` MUST be `PhantomData<_>`", quote!(#ident))
+}
+
+fn has_word_attribute(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter_map(|a| a.interpret_meta())
+        .any(|meta| match meta {
+            Meta::Word(ref ident) => {
+                let ident: &str = ident.as_ref();
+                ident == name
+            }
+            _ => false,
+        })
+}
+
+/// Parses `#[state_machine(timing = "..", trigger = "..")]` into the types of the
+/// timing- and trigger enumeration.
+fn machine_enumerations(attrs: &[Attribute]) -> (Type, Type) {
+    let mut timing = None;
+    let mut trigger = None;
+
+    for meta in attrs.iter().filter_map(|a| a.interpret_meta()) {
+        let list = match meta {
+            Meta::List(list) => list,
+            _ => continue,
+        };
+        {
+            let ident: &str = list.ident.as_ref();
+            if ident != MACHINE_ATTRIBUTE {
+                continue;
+            }
+        }
+
+        for nested in list.nested.iter() {
+            let name_value = match *nested {
+                NestedMeta::Meta(Meta::NameValue(ref name_value)) => name_value,
+                _ => panic!("Expected `{}(timing = \"..\", trigger = \"..\")`", MACHINE_ATTRIBUTE),
+            };
+            let ty: Type = match name_value.lit {
+                Lit::Str(ref value) => syn::parse_str(&value.value())
+                    .expect("The provided string is not a valid type"),
+                _ => panic!("Expected a type wrapped in a string literal"),
+            };
+
+            let key: &str = name_value.ident.as_ref();
+            match key {
+                "timing" => timing = Some(ty),
+                "trigger" => trigger = Some(ty),
+                other => panic!("Unknown {} key `{}`", MACHINE_ATTRIBUTE, other),
+            }
+        }
+    }
+
+    (
+        timing.expect("Missing `timing` type within the state_machine attribute"),
+        trigger.expect("Missing `trigger` type within the state_machine attribute"),
+    )
+}
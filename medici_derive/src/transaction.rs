@@ -0,0 +1,87 @@
+//! Implementation of `#[derive(TransactionContainer)]`.
+
+use quote::Tokens;
+use syn::{Data, DeriveInput, Fields, Ident, Type};
+
+pub fn expand(input: &DeriveInput) -> Tokens {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let variants: Vec<(&Ident, &Type)> = match input.data {
+        Data::Enum(ref data) => data.variants
+            .iter()
+            .map(|v| match v.fields {
+                Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                    (&v.ident, &fields.unnamed.iter().next().unwrap().ty)
+                }
+                _ => {
+                    let ident: &str = v.ident.as_ref();
+                    panic!(
+                        "Variant `{}` MUST wrap exactly one transaction, eg `{}(Transaction)`",
+                        ident, ident
+                    )
+                }
+            })
+            .collect(),
+        _ => panic!("TransactionContainer can only be derived for enumerations"),
+    };
+
+    let variant_names: Vec<&str> = variants.iter().map(|&(v, _)| v.as_ref()).collect();
+    let variant_idents: Vec<&Ident> = variants.iter().map(|&(v, _)| v).collect();
+    // Quote consumes each interpolated iterator, references are used so the same
+    // collection can be repeated multiple times.
+    let variant_names = &variant_names;
+    let variant_idents = &variant_idents;
+
+    // Each variant receives it's own conversions to and from the container.
+    let conversion_impls: Vec<Tokens> = variants
+        .iter()
+        .zip(variant_names.iter())
+        .map(|(&(variant, ty), variant_name)| {
+            quote! {
+                impl #impl_generics ::std::convert::From<#ty> for #name #ty_generics #where_clause {
+                    fn from(x: #ty) -> Self {
+                        #name::#variant(x)
+                    }
+                }
+
+                impl #impl_generics ::std::convert::TryFrom<#name #ty_generics> for #ty #where_clause {
+                    type Error = ::medici_core::error::custom_type::TransactionUnpackError;
+
+                    fn try_from(tc: #name #ty_generics) -> ::std::result::Result<Self, Self::Error> {
+                        // The catch-all arm is unreachable for containers with
+                        // one variant.
+                        #[allow(unreachable_patterns)]
+                        match tc {
+                            #name::#variant(x) => ::std::result::Result::Ok(x),
+                            other => ::std::result::Result::Err(
+                                ::medici_core::error::custom_type::TransactionUnpackError {
+                                    expected: #variant_name,
+                                    factual: other.variant_name(),
+                                },
+                            ),
+                        }
+                    }
+                }
+            }
+        })
+        .collect();
+
+    quote! {
+        impl #impl_generics ::medici_core::marker::TransactionContainer
+            for #name #ty_generics #where_clause
+        {
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Returns the name of the variant wrapping the transaction.
+            pub fn variant_name(&self) -> &'static str {
+                match *self {
+                    #(#name::#variant_idents(_) => #variant_names,)*
+                }
+            }
+        }
+
+        #(#conversion_impls)*
+    }
+}